@@ -1,4 +1,18 @@
-use crate::{SensitiveBytes, ToPrefixedLabel, defs::CiphersuiteId, key_schedule::PreSharedKeyId};
+use crate::{
+    SensitiveBytes, ToPrefixedLabel,
+    defs::{CiphersuiteId, ciphersuite::HpkeKemId},
+    key_schedule::PreSharedKeyId,
+};
+
+mod labeled;
+pub use self::labeled::*;
+mod provider;
+pub use self::provider::*;
+mod signable;
+pub use self::signable::*;
+
+#[cfg(feature = "rustcrypto")]
+pub mod rustcrypto;
 
 pub type Mac = SensitiveBytes;
 pub type HpkePublicKey = SensitiveBytes;
@@ -20,6 +34,20 @@ pub struct KeyPair {
     pub sk: SensitiveBytes,
 }
 
+impl KeyPair {
+    /// Returns the HPKE KEM identified by `kem_id`, if it's a known one
+    pub fn kem(&self) -> Option<HpkeKemId> {
+        HpkeKemId::try_from(self.kem_id).ok()
+    }
+
+    /// Checks that `kem_id` is the KEM mandated by the key pair's ciphersuite
+    pub fn kem_matches_ciphersuite(&self) -> bool {
+        self.ciphersuite
+            .descriptor()
+            .is_some_and(|descriptor| u16::from(descriptor.kem) == self.kem_id)
+    }
+}
+
 macro_rules! impl_keypair_alias {
     ($newtype:ident) => {
         #[derive(Debug, PartialEq, Eq, Clone)]
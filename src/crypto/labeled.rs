@@ -0,0 +1,113 @@
+use crate::{
+    MlsSpecResult, SensitiveBytes, Serializable as _, ToPrefixedLabel as _,
+    crypto::{
+        CipherSuiteProvider, EncryptContext, HashReferenceInput, HpkeCiphertext, HpkePrivateKeyRef,
+        HpkePublicKeyRef, KdfLabel,
+    },
+    defs::{
+        Generation, ProtocolVersion,
+        labels::{HashReferenceKind, KdfLabelKind, PublicKeyEncryptionLabel},
+    },
+    group::HashReference,
+};
+
+/// Labeled cryptographic operations of RFC9420, built on top of any [CipherSuiteProvider].
+///
+/// <https://www.rfc-editor.org/rfc/rfc9420.html#section-5.1>
+///
+/// This trait is implemented for all [CipherSuiteProvider]s.
+pub trait LabeledCrypto: CipherSuiteProvider {
+    /// `ExpandWithLabel(Secret, Label, Context, Length)`
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-8-10>
+    fn expand_with_label(
+        &self,
+        secret: &[u8],
+        label: &KdfLabelKind,
+        context: &[u8],
+        length: u16,
+    ) -> MlsSpecResult<SensitiveBytes> {
+        let label = label.to_prefixed_string(ProtocolVersion::default());
+        let info = KdfLabel {
+            length,
+            label: &label,
+            context,
+        }
+        .to_tls_bytes()?;
+
+        self.kdf_expand(secret, &info, length as usize)
+    }
+
+    /// `DeriveSecret(Secret, Label) = ExpandWithLabel(Secret, Label, "", KDF.Nh)`
+    fn derive_secret(&self, secret: &[u8], label: &KdfLabelKind) -> MlsSpecResult<SensitiveBytes> {
+        self.expand_with_label(secret, label, &[], self.kdf_extract_size() as u16)
+    }
+
+    /// `DeriveTreeSecret(Secret, Label, Generation, Length) = ExpandWithLabel(Secret, Label, Generation, Length)`
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-9.1-4>
+    fn derive_tree_secret(
+        &self,
+        secret: &[u8],
+        label: &KdfLabelKind,
+        generation: Generation,
+        length: u16,
+    ) -> MlsSpecResult<SensitiveBytes> {
+        self.expand_with_label(secret, label, &generation.to_be_bytes(), length)
+    }
+
+    /// `EncryptWithLabel(PublicKey, Label, Context, Plaintext)`
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-5.1.3>
+    fn encrypt_with_label(
+        &self,
+        public_key: HpkePublicKeyRef,
+        label: &PublicKeyEncryptionLabel,
+        context: &[u8],
+        plaintext: &[u8],
+    ) -> MlsSpecResult<HpkeCiphertext> {
+        let info = encrypt_context(label, context)?;
+        self.hpke_seal(public_key, &info, &[], plaintext)
+    }
+
+    /// `DecryptWithLabel(PrivateKey, Label, Context, KEMOutput, Ciphertext)`
+    fn decrypt_with_label(
+        &self,
+        private_key: HpkePrivateKeyRef,
+        public_key: HpkePublicKeyRef,
+        label: &PublicKeyEncryptionLabel,
+        context: &[u8],
+        ciphertext: &HpkeCiphertext,
+    ) -> MlsSpecResult<SensitiveBytes> {
+        let info = encrypt_context(label, context)?;
+        self.hpke_open(ciphertext, private_key, public_key, &info, &[])
+    }
+
+    /// `RefHash(Label, Value)`. Note that `label` is used verbatim here.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-5.2>
+    fn ref_hash(&self, label: &str, value: &[u8]) -> MlsSpecResult<HashReference> {
+        let input = HashReferenceInput { label, value }.to_tls_bytes()?;
+        self.hash(&input)
+    }
+
+    /// `MakeKeyPackageRef(value)` / `MakeProposalRef(value)`, which are `RefHash` with the prefixed label of `kind`
+    fn hash_reference(
+        &self,
+        kind: HashReferenceKind,
+        value: &[u8],
+    ) -> MlsSpecResult<HashReference> {
+        self.ref_hash(&kind.to_prefixed_string(ProtocolVersion::default()), value)
+    }
+}
+
+impl<T: CipherSuiteProvider + ?Sized> LabeledCrypto for T {}
+
+fn encrypt_context(label: &PublicKeyEncryptionLabel, context: &[u8]) -> MlsSpecResult<Vec<u8>> {
+    let label = label.to_prefixed_string(ProtocolVersion::default());
+    EncryptContext {
+        label: &label,
+        context,
+    }
+    .to_tls_bytes()
+}
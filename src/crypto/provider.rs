@@ -0,0 +1,163 @@
+use crate::{
+    MlsSpecResult, SensitiveBytes,
+    crypto::{
+        HpkeCiphertext, HpkeExport, HpkeKeyPair, HpkePrivateKeyRef, HpkePublicKeyRef, Mac,
+        SignatureKeyPair, SignaturePublicKey,
+    },
+    defs::CiphersuiteId,
+};
+
+/// Entry point for all cryptographic operations performed on MLS structures.
+///
+/// A [CryptoProvider] doesn't perform anything by itself, its sole purpose is
+/// to resolve a [CipherSuiteProvider] for a given [CiphersuiteId], which in turn
+/// carries the actual primitives (HPKE, KDF, AEAD, Hash, Signatures).
+///
+/// Everything in this crate that needs to perform cryptography is written against
+/// those traits, so that implementors are free to bring their own backend.
+pub trait CryptoProvider: Send + Sync {
+    type CipherSuiteProvider: CipherSuiteProvider;
+
+    /// Lists all the ciphersuites this provider is able to handle
+    fn supported_ciphersuites(&self) -> Vec<CiphersuiteId>;
+
+    /// Resolves the [CipherSuiteProvider] for the given ciphersuite.
+    ///
+    /// Returns [crate::MlsSpecError::UnsupportedCiphersuite] if the ciphersuite isn't handled by this provider
+    fn cipher_suite_provider(
+        &self,
+        ciphersuite: CiphersuiteId,
+    ) -> MlsSpecResult<Self::CipherSuiteProvider>;
+
+    #[inline]
+    fn supports_ciphersuite(&self, ciphersuite: CiphersuiteId) -> bool {
+        self.supported_ciphersuites().contains(&ciphersuite)
+    }
+}
+
+/// Cryptographic primitives of a single MLS ciphersuite.
+///
+/// <https://www.rfc-editor.org/rfc/rfc9420.html#section-5.1>
+///
+/// All byte formats follow RFC9420: HPKE keys use the `SerializePublicKey` / `SerializePrivateKey`
+/// encodings of RFC9180, and ECDSA signatures are DER-encoded.
+pub trait CipherSuiteProvider: Send + Sync {
+    /// The ciphersuite this provider is bound to
+    fn ciphersuite(&self) -> CiphersuiteId;
+
+    /// Hashes `data` with the ciphersuite's hash algorithm
+    fn hash(&self, data: &[u8]) -> MlsSpecResult<SensitiveBytes>;
+
+    /// Computes `MAC(key, data)`, which is HMAC with the ciphersuite's hash algorithm
+    fn mac(&self, key: &[u8], data: &[u8]) -> MlsSpecResult<Mac>;
+
+    /// `KDF.Extract(salt, ikm)`
+    fn kdf_extract(&self, salt: &[u8], ikm: &[u8]) -> MlsSpecResult<SensitiveBytes>;
+
+    /// `KDF.Expand(prk, info, len)`
+    fn kdf_expand(&self, prk: &[u8], info: &[u8], len: usize) -> MlsSpecResult<SensitiveBytes>;
+
+    /// `KDF.Nh`, the output size of `KDF.Extract`
+    fn kdf_extract_size(&self) -> usize;
+
+    /// Encrypts `plaintext` with the ciphersuite's AEAD. The authentication tag is appended to the ciphertext.
+    fn aead_seal(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> MlsSpecResult<Vec<u8>>;
+
+    /// Decrypts `ciphertext` (which contains the authentication tag) with the ciphersuite's AEAD
+    fn aead_open(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> MlsSpecResult<SensitiveBytes>;
+
+    /// `AEAD.Nk`
+    fn aead_key_size(&self) -> usize;
+
+    /// `AEAD.Nn`
+    fn aead_nonce_size(&self) -> usize;
+
+    /// `AEAD.Nt`, the size of the authentication tag appended by [Self::aead_seal]
+    fn aead_tag_size(&self) -> usize;
+
+    /// HPKE single-shot encryption in base mode (`SealBase`)
+    fn hpke_seal(
+        &self,
+        pk: HpkePublicKeyRef,
+        info: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> MlsSpecResult<HpkeCiphertext>;
+
+    /// HPKE single-shot decryption in base mode (`OpenBase`)
+    fn hpke_open(
+        &self,
+        ciphertext: &HpkeCiphertext,
+        sk: HpkePrivateKeyRef,
+        pk: HpkePublicKeyRef,
+        info: &[u8],
+        aad: &[u8],
+    ) -> MlsSpecResult<SensitiveBytes>;
+
+    /// Sets up a HPKE sender context in base mode, and exports a secret of `len` bytes from it.
+    ///
+    /// This is used for External Commits, see <https://www.rfc-editor.org/rfc/rfc9420.html#section-8.3>
+    fn hpke_export_sender(
+        &self,
+        pk: HpkePublicKeyRef,
+        info: &[u8],
+        exporter_context: &[u8],
+        len: usize,
+    ) -> MlsSpecResult<HpkeExport>;
+
+    /// Sets up a HPKE receiver context in base mode, and exports a secret of `len` bytes from it.
+    fn hpke_export_receiver(
+        &self,
+        kem_output: &[u8],
+        sk: HpkePrivateKeyRef,
+        pk: HpkePublicKeyRef,
+        info: &[u8],
+        exporter_context: &[u8],
+        len: usize,
+    ) -> MlsSpecResult<SensitiveBytes>;
+
+    /// Generates a fresh, random HPKE key pair
+    fn kem_generate(&self) -> MlsSpecResult<HpkeKeyPair>;
+
+    /// `DeriveKeyPair(ikm)` as defined by RFC9180
+    fn kem_derive(&self, ikm: &[u8]) -> MlsSpecResult<HpkeKeyPair>;
+
+    /// Checks that the provided bytes are a valid HPKE public key for this ciphersuite
+    fn kem_public_key_validate(&self, pk: HpkePublicKeyRef) -> MlsSpecResult<()>;
+
+    /// Generates a fresh, random signature key pair
+    fn signature_key_generate(&self) -> MlsSpecResult<SignatureKeyPair>;
+
+    /// Computes the public key matching a signature private key
+    fn signature_key_derive_public(&self, sk: &[u8]) -> MlsSpecResult<SignaturePublicKey>;
+
+    /// Signs `data` with the signature private key `sk`
+    fn sign(&self, sk: &[u8], data: &[u8]) -> MlsSpecResult<SensitiveBytes>;
+
+    /// Verifies the `signature` of `data` with the signature public key `pk`
+    ///
+    /// Returns [crate::MlsSpecError::InvalidSignature] if the signature doesn't match
+    fn verify(&self, pk: &[u8], signature: &[u8], data: &[u8]) -> MlsSpecResult<()>;
+
+    /// Fills `out` with cryptographically secure random bytes
+    fn random_bytes(&self, out: &mut [u8]) -> MlsSpecResult<()>;
+
+    /// Returns `len` cryptographically secure random bytes
+    fn random_bytes_vec(&self, len: usize) -> MlsSpecResult<SensitiveBytes> {
+        let mut out = vec![0u8; len];
+        self.random_bytes(&mut out)?;
+        Ok(out.into())
+    }
+}
@@ -0,0 +1,644 @@
+//! [CryptoProvider] implementation backed by the [RustCrypto](https://github.com/RustCrypto) ecosystem
+//! and the [`hpke`](https://docs.rs/hpke) crate.
+//!
+//! Supported ciphersuites:
+//!
+//! - `MLS_128_DHKEMX25519_AES128GCM_SHA256_ED25519`
+//! - `MLS_128_DHKEMP256_AES128GCM_SHA256_P256`
+//! - `MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_ED25519`
+//! - `MLS_256_DHKEMP521_AES256GCM_SHA512_P521`
+//! - `MLS_256_DHKEMP384_AES256GCM_SHA384_P384`
+//!
+//! The X448/Ed448 ciphersuites aren't supported as RustCrypto doesn't offer a stable implementation of those.
+
+use hpke::{Deserializable as _, Serializable as _};
+
+use crate::{
+    MlsSpecError, MlsSpecResult, SensitiveBytes,
+    crypto::{
+        CipherSuiteProvider, CryptoProvider, HpkeCiphertext, HpkeExport, HpkeKeyPair,
+        HpkePrivateKeyRef, HpkePublicKeyRef, KeyPair, Mac, SignatureKeyPair, SignaturePublicKey,
+    },
+    defs::{
+        CiphersuiteId,
+        ciphersuite::{CiphersuiteDescriptor, HpkeAeadId, HpkeKdfId, SignatureScheme},
+    },
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum RustCryptoError {
+    #[error(transparent)]
+    HpkeError(#[from] hpke::HpkeError),
+    #[error("AEAD operation failed")]
+    AeadError,
+    #[error("Invalid key or nonce length")]
+    InvalidLength,
+    #[error("Invalid key material")]
+    InvalidKeyMaterial,
+    #[error(transparent)]
+    RandError(#[from] rand_core::Error),
+}
+
+impl From<RustCryptoError> for MlsSpecError {
+    fn from(value: RustCryptoError) -> Self {
+        Self::CryptoProviderError(Box::new(value))
+    }
+}
+
+/// Stateless provider that resolves [RustCryptoCipherSuite]s
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RustCryptoProvider;
+
+impl RustCryptoProvider {
+    pub const SUPPORTED_CIPHERSUITES: [u16; 5] = [
+        CiphersuiteId::MLS_128_DHKEMX25519_AES128GCM_SHA256_ED25519,
+        CiphersuiteId::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
+        CiphersuiteId::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_ED25519,
+        CiphersuiteId::MLS_256_DHKEMP521_AES256GCM_SHA512_P521,
+        CiphersuiteId::MLS_256_DHKEMP384_AES256GCM_SHA384_P384,
+    ];
+}
+
+impl CryptoProvider for RustCryptoProvider {
+    type CipherSuiteProvider = RustCryptoCipherSuite;
+
+    fn supported_ciphersuites(&self) -> Vec<CiphersuiteId> {
+        Self::SUPPORTED_CIPHERSUITES
+            .into_iter()
+            .map(CiphersuiteId::new_unchecked)
+            .collect()
+    }
+
+    fn cipher_suite_provider(
+        &self,
+        ciphersuite: CiphersuiteId,
+    ) -> MlsSpecResult<Self::CipherSuiteProvider> {
+        RustCryptoCipherSuite::new(ciphersuite)
+    }
+}
+
+/// RustCrypto implementation of the primitives of a single ciphersuite
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RustCryptoCipherSuite {
+    ciphersuite: CiphersuiteId,
+    descriptor: &'static CiphersuiteDescriptor,
+}
+
+impl RustCryptoCipherSuite {
+    pub fn new(ciphersuite: CiphersuiteId) -> MlsSpecResult<Self> {
+        let descriptor = ciphersuite
+            .descriptor()
+            .filter(|_| RustCryptoProvider::SUPPORTED_CIPHERSUITES.contains(&*ciphersuite))
+            .ok_or(MlsSpecError::UnsupportedCiphersuite(ciphersuite))?;
+
+        Ok(Self {
+            ciphersuite,
+            descriptor,
+        })
+    }
+
+    fn keypair(&self, pk: Vec<u8>, sk: Vec<u8>) -> KeyPair {
+        KeyPair {
+            kem_id: self.descriptor.kem.into(),
+            ciphersuite: self.ciphersuite,
+            pk: pk.into(),
+            sk: sk.into(),
+        }
+    }
+
+    fn unsupported(&self) -> MlsSpecError {
+        MlsSpecError::UnsupportedCiphersuite(self.ciphersuite)
+    }
+}
+
+/// Dispatches a generic HPKE function over the concrete (AEAD, KDF, KEM) types of the ciphersuite
+macro_rules! dispatch_hpke {
+    ($self:ident, $func:ident($($arg:expr),*)) => {
+        match *$self.ciphersuite {
+            CiphersuiteId::MLS_128_DHKEMX25519_AES128GCM_SHA256_ED25519 => $func::<
+                hpke::aead::AesGcm128,
+                hpke::kdf::HkdfSha256,
+                hpke::kem::X25519HkdfSha256,
+            >($($arg),*),
+            CiphersuiteId::MLS_128_DHKEMP256_AES128GCM_SHA256_P256 => $func::<
+                hpke::aead::AesGcm128,
+                hpke::kdf::HkdfSha256,
+                hpke::kem::DhP256HkdfSha256,
+            >($($arg),*),
+            CiphersuiteId::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_ED25519 => $func::<
+                hpke::aead::ChaCha20Poly1305,
+                hpke::kdf::HkdfSha256,
+                hpke::kem::X25519HkdfSha256,
+            >($($arg),*),
+            CiphersuiteId::MLS_256_DHKEMP521_AES256GCM_SHA512_P521 => $func::<
+                hpke::aead::AesGcm256,
+                hpke::kdf::HkdfSha512,
+                hpke::kem::DhP521HkdfSha512,
+            >($($arg),*),
+            CiphersuiteId::MLS_256_DHKEMP384_AES256GCM_SHA384_P384 => $func::<
+                hpke::aead::AesGcm256,
+                hpke::kdf::HkdfSha384,
+                hpke::kem::DhP384HkdfSha384,
+            >($($arg),*),
+            _ => Err($self.unsupported()),
+        }
+    };
+}
+
+/// Dispatches a generic function over the concrete HPKE KEM type of the ciphersuite
+macro_rules! dispatch_kem {
+    ($self:ident, $func:ident($($arg:expr),*)) => {
+        match *$self.ciphersuite {
+            CiphersuiteId::MLS_128_DHKEMX25519_AES128GCM_SHA256_ED25519
+            | CiphersuiteId::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_ED25519 => {
+                $func::<hpke::kem::X25519HkdfSha256>($($arg),*)
+            }
+            CiphersuiteId::MLS_128_DHKEMP256_AES128GCM_SHA256_P256 => {
+                $func::<hpke::kem::DhP256HkdfSha256>($($arg),*)
+            }
+            CiphersuiteId::MLS_256_DHKEMP521_AES256GCM_SHA512_P521 => {
+                $func::<hpke::kem::DhP521HkdfSha512>($($arg),*)
+            }
+            CiphersuiteId::MLS_256_DHKEMP384_AES256GCM_SHA384_P384 => {
+                $func::<hpke::kem::DhP384HkdfSha384>($($arg),*)
+            }
+            _ => Err($self.unsupported()),
+        }
+    };
+}
+
+/// Dispatches a generic function over the concrete hash type of the ciphersuite
+macro_rules! dispatch_hash {
+    ($self:ident, $func:ident($($arg:expr),*)) => {
+        match $self.descriptor.kdf {
+            HpkeKdfId::HkdfSha256 => $func::<sha2::Sha256>($($arg),*),
+            HpkeKdfId::HkdfSha384 => $func::<sha2::Sha384>($($arg),*),
+            HpkeKdfId::HkdfSha512 => $func::<sha2::Sha512>($($arg),*),
+        }
+    };
+}
+
+fn hash<H: sha2::Digest>(data: &[u8]) -> MlsSpecResult<SensitiveBytes> {
+    Ok(H::digest(data).to_vec().into())
+}
+
+fn mac<H>(key: &[u8], data: &[u8]) -> MlsSpecResult<Mac>
+where
+    hmac::SimpleHmac<H>: hmac::Mac + hmac::digest::KeyInit,
+    H: hmac::digest::Digest + hmac::digest::core_api::BlockSizeUser,
+{
+    use hmac::Mac as _;
+    let mut hmac = <hmac::SimpleHmac<H> as hmac::digest::KeyInit>::new_from_slice(key)
+        .map_err(|_| RustCryptoError::InvalidLength)?;
+    hmac.update(data);
+    Ok(hmac.finalize().into_bytes().to_vec().into())
+}
+
+fn kdf_extract<H>(salt: &[u8], ikm: &[u8]) -> MlsSpecResult<SensitiveBytes>
+where
+    H: hmac::digest::Digest + hmac::digest::core_api::BlockSizeUser + Clone,
+{
+    let (prk, _) = hkdf::SimpleHkdf::<H>::extract(Some(salt), ikm);
+    Ok(prk.to_vec().into())
+}
+
+fn kdf_expand<H>(prk: &[u8], info: &[u8], len: usize) -> MlsSpecResult<SensitiveBytes>
+where
+    H: hmac::digest::Digest + hmac::digest::core_api::BlockSizeUser + Clone,
+{
+    let hkdf = hkdf::SimpleHkdf::<H>::from_prk(prk).map_err(|_| RustCryptoError::InvalidLength)?;
+    let mut out = vec![0u8; len];
+    hkdf.expand(info, &mut out)
+        .map_err(|_| RustCryptoError::InvalidLength)?;
+    Ok(out.into())
+}
+
+fn hpke_seal<A: hpke::aead::Aead, Kdf: hpke::kdf::Kdf, Kem: hpke::Kem>(
+    pk: &[u8],
+    info: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> MlsSpecResult<HpkeCiphertext> {
+    let pk = Kem::PublicKey::from_bytes(pk).map_err(RustCryptoError::from)?;
+    let (kem_output, ciphertext) = hpke::single_shot_seal::<A, Kdf, Kem, _>(
+        &hpke::OpModeS::Base,
+        &pk,
+        info,
+        plaintext,
+        aad,
+        &mut rand_core::OsRng,
+    )
+    .map_err(RustCryptoError::from)?;
+
+    Ok(HpkeCiphertext {
+        kem_output: kem_output.to_bytes().to_vec().into(),
+        ciphertext: ciphertext.into(),
+    })
+}
+
+fn hpke_open<A: hpke::aead::Aead, Kdf: hpke::kdf::Kdf, Kem: hpke::Kem>(
+    ciphertext: &HpkeCiphertext,
+    sk: &[u8],
+    info: &[u8],
+    aad: &[u8],
+) -> MlsSpecResult<SensitiveBytes> {
+    let sk = Kem::PrivateKey::from_bytes(sk).map_err(RustCryptoError::from)?;
+    let kem_output =
+        Kem::EncappedKey::from_bytes(&ciphertext.kem_output).map_err(RustCryptoError::from)?;
+    let plaintext = hpke::single_shot_open::<A, Kdf, Kem>(
+        &hpke::OpModeR::Base,
+        &sk,
+        &kem_output,
+        info,
+        &ciphertext.ciphertext,
+        aad,
+    )
+    .map_err(RustCryptoError::from)?;
+
+    Ok(plaintext.into())
+}
+
+fn hpke_export_sender<A: hpke::aead::Aead, Kdf: hpke::kdf::Kdf, Kem: hpke::Kem>(
+    pk: &[u8],
+    info: &[u8],
+    exporter_context: &[u8],
+    len: usize,
+) -> MlsSpecResult<HpkeExport> {
+    let pk = Kem::PublicKey::from_bytes(pk).map_err(RustCryptoError::from)?;
+    let (kem_output, ctx) = hpke::setup_sender::<A, Kdf, Kem, _>(
+        &hpke::OpModeS::Base,
+        &pk,
+        info,
+        &mut rand_core::OsRng,
+    )
+    .map_err(RustCryptoError::from)?;
+
+    let mut export = vec![0u8; len];
+    ctx.export(exporter_context, &mut export)
+        .map_err(RustCryptoError::from)?;
+
+    Ok(HpkeExport {
+        kem_output: kem_output.to_bytes().to_vec().into(),
+        export: export.into(),
+    })
+}
+
+fn hpke_export_receiver<A: hpke::aead::Aead, Kdf: hpke::kdf::Kdf, Kem: hpke::Kem>(
+    kem_output: &[u8],
+    sk: &[u8],
+    info: &[u8],
+    exporter_context: &[u8],
+    len: usize,
+) -> MlsSpecResult<SensitiveBytes> {
+    let sk = Kem::PrivateKey::from_bytes(sk).map_err(RustCryptoError::from)?;
+    let kem_output = Kem::EncappedKey::from_bytes(kem_output).map_err(RustCryptoError::from)?;
+    let ctx = hpke::setup_receiver::<A, Kdf, Kem>(&hpke::OpModeR::Base, &sk, &kem_output, info)
+        .map_err(RustCryptoError::from)?;
+
+    let mut export = vec![0u8; len];
+    ctx.export(exporter_context, &mut export)
+        .map_err(RustCryptoError::from)?;
+
+    Ok(export.into())
+}
+
+fn kem_derive<Kem: hpke::Kem>(ikm: &[u8]) -> MlsSpecResult<(Vec<u8>, Vec<u8>)> {
+    let (sk, pk) = Kem::derive_keypair(ikm);
+    Ok((pk.to_bytes().to_vec(), sk.to_bytes().to_vec()))
+}
+
+fn kem_public_key_validate<Kem: hpke::Kem>(pk: &[u8]) -> MlsSpecResult<()> {
+    Kem::PublicKey::from_bytes(pk).map_err(RustCryptoError::from)?;
+    Ok(())
+}
+
+/// Implements ECDSA signing/verification for a NIST curve. Keys are raw scalars / uncompressed SEC1 points,
+/// and signatures are DER-encoded as mandated by RFC9420
+macro_rules! impl_ecdsa {
+    ($modname:ident, $curve:ident) => {
+        mod $modname {
+            use ::$curve::ecdsa::{
+                Signature, SigningKey, VerifyingKey,
+                signature::{Signer as _, Verifier as _},
+            };
+
+            use super::RustCryptoError;
+            use crate::{MlsSpecError, MlsSpecResult};
+
+            pub(super) fn generate() -> (Vec<u8>, Vec<u8>) {
+                let sk = SigningKey::random(&mut rand_core::OsRng);
+                let pk = VerifyingKey::from(&sk).to_encoded_point(false);
+                (pk.as_bytes().to_vec(), sk.to_bytes().to_vec())
+            }
+
+            pub(super) fn derive_public(sk: &[u8]) -> MlsSpecResult<Vec<u8>> {
+                let sk =
+                    SigningKey::from_slice(sk).map_err(|_| RustCryptoError::InvalidKeyMaterial)?;
+                Ok(VerifyingKey::from(&sk)
+                    .to_encoded_point(false)
+                    .as_bytes()
+                    .to_vec())
+            }
+
+            pub(super) fn sign(sk: &[u8], data: &[u8]) -> MlsSpecResult<Vec<u8>> {
+                let sk =
+                    SigningKey::from_slice(sk).map_err(|_| RustCryptoError::InvalidKeyMaterial)?;
+                let signature: Signature = sk.sign(data);
+                Ok(signature.to_der().as_bytes().to_vec())
+            }
+
+            pub(super) fn verify(pk: &[u8], signature: &[u8], data: &[u8]) -> MlsSpecResult<()> {
+                let pk = VerifyingKey::from_sec1_bytes(pk)
+                    .map_err(|_| RustCryptoError::InvalidKeyMaterial)?;
+                let signature =
+                    Signature::from_der(signature).map_err(|_| MlsSpecError::InvalidSignature)?;
+                pk.verify(data, &signature)
+                    .map_err(|_| MlsSpecError::InvalidSignature)
+            }
+        }
+    };
+}
+
+impl_ecdsa!(ecdsa_p256, p256);
+impl_ecdsa!(ecdsa_p384, p384);
+impl_ecdsa!(ecdsa_p521, p521);
+
+mod ed25519 {
+    use ed25519_dalek::{SecretKey, Signature, Signer as _, SigningKey, VerifyingKey};
+
+    use super::RustCryptoError;
+    use crate::{MlsSpecError, MlsSpecResult};
+
+    fn signing_key(sk: &[u8]) -> MlsSpecResult<SigningKey> {
+        let sk: &SecretKey = sk
+            .try_into()
+            .map_err(|_| RustCryptoError::InvalidKeyMaterial)?;
+        Ok(SigningKey::from_bytes(sk))
+    }
+
+    pub(super) fn generate() -> MlsSpecResult<(Vec<u8>, Vec<u8>)> {
+        let mut sk = SecretKey::default();
+        rand_core::RngCore::try_fill_bytes(&mut rand_core::OsRng, &mut sk)
+            .map_err(RustCryptoError::from)?;
+        let sk = SigningKey::from_bytes(&sk);
+        Ok((
+            sk.verifying_key().to_bytes().to_vec(),
+            sk.to_bytes().to_vec(),
+        ))
+    }
+
+    pub(super) fn derive_public(sk: &[u8]) -> MlsSpecResult<Vec<u8>> {
+        Ok(signing_key(sk)?.verifying_key().to_bytes().to_vec())
+    }
+
+    pub(super) fn sign(sk: &[u8], data: &[u8]) -> MlsSpecResult<Vec<u8>> {
+        Ok(signing_key(sk)?.sign(data).to_bytes().to_vec())
+    }
+
+    pub(super) fn verify(pk: &[u8], signature: &[u8], data: &[u8]) -> MlsSpecResult<()> {
+        let pk: &[u8; ed25519_dalek::PUBLIC_KEY_LENGTH] = pk
+            .try_into()
+            .map_err(|_| RustCryptoError::InvalidKeyMaterial)?;
+        let pk = VerifyingKey::from_bytes(pk).map_err(|_| RustCryptoError::InvalidKeyMaterial)?;
+        let signature =
+            Signature::from_slice(signature).map_err(|_| MlsSpecError::InvalidSignature)?;
+        pk.verify_strict(data, &signature)
+            .map_err(|_| MlsSpecError::InvalidSignature)
+    }
+}
+
+impl CipherSuiteProvider for RustCryptoCipherSuite {
+    fn ciphersuite(&self) -> CiphersuiteId {
+        self.ciphersuite
+    }
+
+    fn hash(&self, data: &[u8]) -> MlsSpecResult<SensitiveBytes> {
+        dispatch_hash!(self, hash(data))
+    }
+
+    fn mac(&self, key: &[u8], data: &[u8]) -> MlsSpecResult<Mac> {
+        dispatch_hash!(self, mac(key, data))
+    }
+
+    fn kdf_extract(&self, salt: &[u8], ikm: &[u8]) -> MlsSpecResult<SensitiveBytes> {
+        dispatch_hash!(self, kdf_extract(salt, ikm))
+    }
+
+    fn kdf_expand(&self, prk: &[u8], info: &[u8], len: usize) -> MlsSpecResult<SensitiveBytes> {
+        dispatch_hash!(self, kdf_expand(prk, info, len))
+    }
+
+    fn kdf_extract_size(&self) -> usize {
+        self.descriptor.nh()
+    }
+
+    fn aead_seal(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> MlsSpecResult<Vec<u8>> {
+        use aes_gcm::aead::{Aead as _, KeyInit as _, Payload};
+
+        if nonce.len() != self.aead_nonce_size() {
+            return Err(RustCryptoError::InvalidLength.into());
+        }
+
+        let payload = Payload {
+            msg: plaintext,
+            aad,
+        };
+        let nonce = aes_gcm::Nonce::from_slice(nonce);
+        let ciphertext = match self.descriptor.aead {
+            HpkeAeadId::Aes128Gcm => aes_gcm::Aes128Gcm::new_from_slice(key)
+                .map_err(|_| RustCryptoError::InvalidLength)?
+                .encrypt(nonce, payload),
+            HpkeAeadId::Aes256Gcm => aes_gcm::Aes256Gcm::new_from_slice(key)
+                .map_err(|_| RustCryptoError::InvalidLength)?
+                .encrypt(nonce, payload),
+            HpkeAeadId::ChaCha20Poly1305 => chacha20poly1305::ChaCha20Poly1305::new_from_slice(key)
+                .map_err(|_| RustCryptoError::InvalidLength)?
+                .encrypt(nonce, payload),
+        }
+        .map_err(|_| RustCryptoError::AeadError)?;
+
+        Ok(ciphertext)
+    }
+
+    fn aead_open(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> MlsSpecResult<SensitiveBytes> {
+        use aes_gcm::aead::{Aead as _, KeyInit as _, Payload};
+
+        if nonce.len() != self.aead_nonce_size() {
+            return Err(RustCryptoError::InvalidLength.into());
+        }
+
+        let payload = Payload {
+            msg: ciphertext,
+            aad,
+        };
+        let nonce = aes_gcm::Nonce::from_slice(nonce);
+        let plaintext = match self.descriptor.aead {
+            HpkeAeadId::Aes128Gcm => aes_gcm::Aes128Gcm::new_from_slice(key)
+                .map_err(|_| RustCryptoError::InvalidLength)?
+                .decrypt(nonce, payload),
+            HpkeAeadId::Aes256Gcm => aes_gcm::Aes256Gcm::new_from_slice(key)
+                .map_err(|_| RustCryptoError::InvalidLength)?
+                .decrypt(nonce, payload),
+            HpkeAeadId::ChaCha20Poly1305 => chacha20poly1305::ChaCha20Poly1305::new_from_slice(key)
+                .map_err(|_| RustCryptoError::InvalidLength)?
+                .decrypt(nonce, payload),
+        }
+        .map_err(|_| RustCryptoError::AeadError)?;
+
+        Ok(plaintext.into())
+    }
+
+    fn aead_key_size(&self) -> usize {
+        self.descriptor.nk()
+    }
+
+    fn aead_nonce_size(&self) -> usize {
+        self.descriptor.nn()
+    }
+
+    fn aead_tag_size(&self) -> usize {
+        self.descriptor.nt()
+    }
+
+    fn hpke_seal(
+        &self,
+        pk: HpkePublicKeyRef,
+        info: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> MlsSpecResult<HpkeCiphertext> {
+        dispatch_hpke!(self, hpke_seal(pk, info, aad, plaintext))
+    }
+
+    fn hpke_open(
+        &self,
+        ciphertext: &HpkeCiphertext,
+        sk: HpkePrivateKeyRef,
+        _pk: HpkePublicKeyRef,
+        info: &[u8],
+        aad: &[u8],
+    ) -> MlsSpecResult<SensitiveBytes> {
+        dispatch_hpke!(self, hpke_open(ciphertext, sk, info, aad))
+    }
+
+    fn hpke_export_sender(
+        &self,
+        pk: HpkePublicKeyRef,
+        info: &[u8],
+        exporter_context: &[u8],
+        len: usize,
+    ) -> MlsSpecResult<HpkeExport> {
+        dispatch_hpke!(self, hpke_export_sender(pk, info, exporter_context, len))
+    }
+
+    fn hpke_export_receiver(
+        &self,
+        kem_output: &[u8],
+        sk: HpkePrivateKeyRef,
+        _pk: HpkePublicKeyRef,
+        info: &[u8],
+        exporter_context: &[u8],
+        len: usize,
+    ) -> MlsSpecResult<SensitiveBytes> {
+        dispatch_hpke!(
+            self,
+            hpke_export_receiver(kem_output, sk, info, exporter_context, len)
+        )
+    }
+
+    fn kem_generate(&self) -> MlsSpecResult<HpkeKeyPair> {
+        let ikm = self.random_bytes_vec(self.descriptor.kem.private_key_size())?;
+        self.kem_derive(&ikm)
+    }
+
+    fn kem_derive(&self, ikm: &[u8]) -> MlsSpecResult<HpkeKeyPair> {
+        let (pk, sk) = dispatch_kem!(self, kem_derive(ikm))?;
+        Ok(self.keypair(pk, sk).into())
+    }
+
+    fn kem_public_key_validate(&self, pk: HpkePublicKeyRef) -> MlsSpecResult<()> {
+        dispatch_kem!(self, kem_public_key_validate(pk))
+    }
+
+    fn signature_key_generate(&self) -> MlsSpecResult<SignatureKeyPair> {
+        let (pk, sk) = match self.descriptor.signature_scheme {
+            SignatureScheme::Ed25519 => ed25519::generate()?,
+            SignatureScheme::EcdsaSecp256r1Sha256 => ecdsa_p256::generate(),
+            SignatureScheme::EcdsaSecp384r1Sha384 => ecdsa_p384::generate(),
+            SignatureScheme::EcdsaSecp521r1Sha512 => ecdsa_p521::generate(),
+            SignatureScheme::Ed448 => return Err(self.unsupported()),
+        };
+        Ok(self.keypair(pk, sk).into())
+    }
+
+    fn signature_key_derive_public(&self, sk: &[u8]) -> MlsSpecResult<SignaturePublicKey> {
+        Ok(match self.descriptor.signature_scheme {
+            SignatureScheme::Ed25519 => ed25519::derive_public(sk)?,
+            SignatureScheme::EcdsaSecp256r1Sha256 => ecdsa_p256::derive_public(sk)?,
+            SignatureScheme::EcdsaSecp384r1Sha384 => ecdsa_p384::derive_public(sk)?,
+            SignatureScheme::EcdsaSecp521r1Sha512 => ecdsa_p521::derive_public(sk)?,
+            SignatureScheme::Ed448 => return Err(self.unsupported()),
+        }
+        .into())
+    }
+
+    fn sign(&self, sk: &[u8], data: &[u8]) -> MlsSpecResult<SensitiveBytes> {
+        Ok(match self.descriptor.signature_scheme {
+            SignatureScheme::Ed25519 => ed25519::sign(sk, data)?,
+            SignatureScheme::EcdsaSecp256r1Sha256 => ecdsa_p256::sign(sk, data)?,
+            SignatureScheme::EcdsaSecp384r1Sha384 => ecdsa_p384::sign(sk, data)?,
+            SignatureScheme::EcdsaSecp521r1Sha512 => ecdsa_p521::sign(sk, data)?,
+            SignatureScheme::Ed448 => return Err(self.unsupported()),
+        }
+        .into())
+    }
+
+    fn verify(&self, pk: &[u8], signature: &[u8], data: &[u8]) -> MlsSpecResult<()> {
+        match self.descriptor.signature_scheme {
+            SignatureScheme::Ed25519 => ed25519::verify(pk, signature, data),
+            SignatureScheme::EcdsaSecp256r1Sha256 => ecdsa_p256::verify(pk, signature, data),
+            SignatureScheme::EcdsaSecp384r1Sha384 => ecdsa_p384::verify(pk, signature, data),
+            SignatureScheme::EcdsaSecp521r1Sha512 => ecdsa_p521::verify(pk, signature, data),
+            SignatureScheme::Ed448 => Err(self.unsupported()),
+        }
+    }
+
+    fn random_bytes(&self, out: &mut [u8]) -> MlsSpecResult<()> {
+        rand_core::RngCore::try_fill_bytes(&mut rand_core::OsRng, out)
+            .map_err(RustCryptoError::from)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aead_tag_size_matches_seal_overhead() {
+        for ciphersuite in RustCryptoProvider.supported_ciphersuites() {
+            let provider = RustCryptoProvider
+                .cipher_suite_provider(ciphersuite)
+                .unwrap();
+            let key = vec![0; provider.aead_key_size()];
+            let nonce = vec![0; provider.aead_nonce_size()];
+            let ciphertext = provider.aead_seal(&key, &nonce, &[], b"plaintext").unwrap();
+            assert_eq!(
+                ciphertext.len(),
+                b"plaintext".len() + provider.aead_tag_size()
+            );
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{
+    MlsSpecResult, SensitiveBytes, Serializable, ToPrefixedLabel as _,
+    crypto::{CipherSuiteProvider, SignContent},
+    defs::{ProtocolVersion, labels::SignatureLabel},
+};
+
+/// Binds a to-be-signed structure to the [SignatureLabel] it must be signed with,
+/// and provides `SignWithLabel` / `VerifyWithLabel` on top of it.
+///
+/// <https://www.rfc-editor.org/rfc/rfc9420.html#section-5.1.2>
+pub trait Signable: Serializable {
+    const SIGNATURE_LABEL: SignatureLabel;
+
+    /// Produces the serialized `SignContent` of this structure, which is the actual input of the signature algorithm
+    fn to_sign_content(&self) -> MlsSpecResult<Vec<u8>> {
+        let label = Self::SIGNATURE_LABEL.to_prefixed_string(ProtocolVersion::default());
+        let content = self.to_tls_bytes()?;
+        SignContent {
+            label: &label,
+            content: &content,
+        }
+        .to_tls_bytes()
+    }
+
+    /// `SignWithLabel(SignatureKey, Label, Content)`
+    fn sign(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        signature_private_key: &[u8],
+    ) -> MlsSpecResult<SensitiveBytes> {
+        provider.sign(signature_private_key, &self.to_sign_content()?)
+    }
+
+    /// `VerifyWithLabel(VerificationKey, Label, Content, SignatureValue)`
+    ///
+    /// Returns [crate::MlsSpecError::InvalidSignature] if the signature doesn't match
+    fn verify(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        signature_public_key: &[u8],
+        signature: &[u8],
+    ) -> MlsSpecResult<()> {
+        provider.verify(signature_public_key, signature, &self.to_sign_content()?)
+    }
+}
+
+macro_rules! impl_signable {
+    ($(#[cfg($attr:meta)])* $tbs:ty => $label:ident) => {
+        $(#[cfg($attr)])*
+        impl Signable for $tbs {
+            const SIGNATURE_LABEL: SignatureLabel = SignatureLabel::$label;
+        }
+    };
+}
+
+impl_signable!(crate::messages::FramedContentTBS<'_> => FramedContentTBS);
+impl_signable!(crate::tree::leaf_node::LeafNodeTBS<'_> => LeafNodeTBS);
+impl_signable!(crate::key_package::KeyPackageTBS<'_> => KeyPackageTBS);
+impl_signable!(crate::group::group_info::GroupInfoTBS<'_> => GroupInfoTBS);
+impl_signable!(
+    #[cfg(feature = "draft-ietf-mls-extensions")]
+    crate::drafts::mls_extensions::targeted_message::TargetedMessageTBS<'_> => TargetedMessagesTBS
+);
+impl_signable!(
+    #[cfg(feature = "draft-ietf-mls-extensions")]
+    crate::drafts::mls_extensions::multi_credentials::CredentialBindingTBS<'_> => CredentialBindingTBS
+);
+impl_signable!(
+    #[cfg(feature = "draft-kohbrok-mls-associated-parties")]
+    crate::drafts::associated_parties::AssociatedPartyEntryTBS<'_> => AssociatedPartyEntryTBS
+);
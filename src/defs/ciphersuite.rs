@@ -0,0 +1,345 @@
+use crate::defs::CiphersuiteId;
+
+macro_rules! impl_u16_algorithm_id {
+    ($typename:ident) => {
+        impl From<$typename> for u16 {
+            fn from(value: $typename) -> Self {
+                value as u16
+            }
+        }
+
+        impl TryFrom<u16> for $typename {
+            type Error = crate::MlsSpecError;
+            fn try_from(value: u16) -> Result<Self, Self::Error> {
+                Self::ALL
+                    .into_iter()
+                    .find(|id| *id as u16 == value)
+                    .ok_or(crate::MlsSpecError::InvalidSpecValue)
+            }
+        }
+    };
+}
+
+/// HPKE KEM identifiers used by MLS ciphersuites
+///
+/// <https://www.rfc-editor.org/rfc/rfc9180.html#section-7.1>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde_repr::Serialize_repr, serde_repr::Deserialize_repr)
+)]
+#[repr(u16)]
+pub enum HpkeKemId {
+    #[strum(serialize = "DHKEM(P-256, HKDF-SHA256)")]
+    DhKemP256HkdfSha256 = 0x0010,
+    #[strum(serialize = "DHKEM(P-384, HKDF-SHA384)")]
+    DhKemP384HkdfSha384 = 0x0011,
+    #[strum(serialize = "DHKEM(P-521, HKDF-SHA512)")]
+    DhKemP521HkdfSha512 = 0x0012,
+    #[strum(serialize = "DHKEM(X25519, HKDF-SHA256)")]
+    DhKemX25519HkdfSha256 = 0x0020,
+    #[strum(serialize = "DHKEM(X448, HKDF-SHA512)")]
+    DhKemX448HkdfSha512 = 0x0021,
+}
+
+impl HpkeKemId {
+    const ALL: [Self; 5] = [
+        Self::DhKemP256HkdfSha256,
+        Self::DhKemP384HkdfSha384,
+        Self::DhKemP521HkdfSha512,
+        Self::DhKemX25519HkdfSha256,
+        Self::DhKemX448HkdfSha512,
+    ];
+
+    /// `Npk`, the length of a serialized public key
+    pub const fn public_key_size(&self) -> usize {
+        match self {
+            Self::DhKemP256HkdfSha256 => 65,
+            Self::DhKemP384HkdfSha384 => 97,
+            Self::DhKemP521HkdfSha512 => 133,
+            Self::DhKemX25519HkdfSha256 => 32,
+            Self::DhKemX448HkdfSha512 => 56,
+        }
+    }
+
+    /// `Nsk`, the length of a serialized private key
+    pub const fn private_key_size(&self) -> usize {
+        match self {
+            Self::DhKemP256HkdfSha256 | Self::DhKemX25519HkdfSha256 => 32,
+            Self::DhKemP384HkdfSha384 => 48,
+            Self::DhKemP521HkdfSha512 => 66,
+            Self::DhKemX448HkdfSha512 => 56,
+        }
+    }
+
+    /// `Nenc`, the length of the encapsulated key (`kem_output`)
+    pub const fn enc_size(&self) -> usize {
+        self.public_key_size()
+    }
+}
+
+impl_u16_algorithm_id!(HpkeKemId);
+
+/// HPKE KDF identifiers used by MLS ciphersuites
+///
+/// <https://www.rfc-editor.org/rfc/rfc9180.html#section-7.2>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde_repr::Serialize_repr, serde_repr::Deserialize_repr)
+)]
+#[repr(u16)]
+pub enum HpkeKdfId {
+    #[strum(serialize = "HKDF-SHA256")]
+    HkdfSha256 = 0x0001,
+    #[strum(serialize = "HKDF-SHA384")]
+    HkdfSha384 = 0x0002,
+    #[strum(serialize = "HKDF-SHA512")]
+    HkdfSha512 = 0x0003,
+}
+
+impl HpkeKdfId {
+    const ALL: [Self; 3] = [Self::HkdfSha256, Self::HkdfSha384, Self::HkdfSha512];
+
+    /// `Nh`, the output size of the underlying hash function and of `KDF.Extract`
+    pub const fn hash_size(&self) -> usize {
+        match self {
+            Self::HkdfSha256 => 32,
+            Self::HkdfSha384 => 48,
+            Self::HkdfSha512 => 64,
+        }
+    }
+}
+
+impl_u16_algorithm_id!(HpkeKdfId);
+
+/// HPKE AEAD identifiers used by MLS ciphersuites
+///
+/// <https://www.rfc-editor.org/rfc/rfc9180.html#section-7.3>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde_repr::Serialize_repr, serde_repr::Deserialize_repr)
+)]
+#[repr(u16)]
+pub enum HpkeAeadId {
+    #[strum(serialize = "AES-128-GCM")]
+    Aes128Gcm = 0x0001,
+    #[strum(serialize = "AES-256-GCM")]
+    Aes256Gcm = 0x0002,
+    #[strum(serialize = "ChaCha20Poly1305")]
+    ChaCha20Poly1305 = 0x0003,
+}
+
+impl HpkeAeadId {
+    const ALL: [Self; 3] = [Self::Aes128Gcm, Self::Aes256Gcm, Self::ChaCha20Poly1305];
+
+    /// `Nk`, the length of an AEAD key
+    pub const fn key_size(&self) -> usize {
+        match self {
+            Self::Aes128Gcm => 16,
+            Self::Aes256Gcm | Self::ChaCha20Poly1305 => 32,
+        }
+    }
+
+    /// `Nn`, the length of an AEAD nonce
+    pub const fn nonce_size(&self) -> usize {
+        12
+    }
+
+    /// `Nt`, the length of an AEAD authentication tag
+    pub const fn tag_size(&self) -> usize {
+        16
+    }
+}
+
+impl_u16_algorithm_id!(HpkeAeadId);
+
+/// TLS 1.3 signature schemes used by MLS ciphersuites
+///
+/// <https://www.rfc-editor.org/rfc/rfc8446.html#section-4.2.3>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde_repr::Serialize_repr, serde_repr::Deserialize_repr)
+)]
+#[repr(u16)]
+pub enum SignatureScheme {
+    #[strum(serialize = "ecdsa_secp256r1_sha256")]
+    EcdsaSecp256r1Sha256 = 0x0403,
+    #[strum(serialize = "ecdsa_secp384r1_sha384")]
+    EcdsaSecp384r1Sha384 = 0x0503,
+    #[strum(serialize = "ecdsa_secp521r1_sha512")]
+    EcdsaSecp521r1Sha512 = 0x0603,
+    #[strum(serialize = "ed25519")]
+    Ed25519 = 0x0807,
+    #[strum(serialize = "ed448")]
+    Ed448 = 0x0808,
+}
+
+impl SignatureScheme {
+    const ALL: [Self; 5] = [
+        Self::EcdsaSecp256r1Sha256,
+        Self::EcdsaSecp384r1Sha384,
+        Self::EcdsaSecp521r1Sha512,
+        Self::Ed25519,
+        Self::Ed448,
+    ];
+}
+
+impl_u16_algorithm_id!(SignatureScheme);
+
+/// Description of the primitives composing a MLS ciphersuite
+///
+/// <https://www.rfc-editor.org/rfc/rfc9420.html#section-17.1>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CiphersuiteDescriptor {
+    pub ciphersuite: CiphersuiteId,
+    /// Security level in bits, as indicated by the `MLS_{LVL}_` prefix of the ciphersuite name
+    pub security_level: u16,
+    pub kem: HpkeKemId,
+    pub kdf: HpkeKdfId,
+    pub aead: HpkeAeadId,
+    pub signature_scheme: SignatureScheme,
+}
+
+impl CiphersuiteDescriptor {
+    const fn new(
+        ciphersuite: u16,
+        security_level: u16,
+        kem: HpkeKemId,
+        kdf: HpkeKdfId,
+        aead: HpkeAeadId,
+        signature_scheme: SignatureScheme,
+    ) -> Self {
+        Self {
+            ciphersuite: CiphersuiteId::new_unchecked(ciphersuite),
+            security_level,
+            kem,
+            kdf,
+            aead,
+            signature_scheme,
+        }
+    }
+
+    /// `Nh`, the output size of the ciphersuite's hash function
+    #[inline]
+    pub const fn nh(&self) -> usize {
+        self.kdf.hash_size()
+    }
+
+    /// `Nk`, the length of the ciphersuite's AEAD key
+    #[inline]
+    pub const fn nk(&self) -> usize {
+        self.aead.key_size()
+    }
+
+    /// `Nn`, the length of the ciphersuite's AEAD nonce
+    #[inline]
+    pub const fn nn(&self) -> usize {
+        self.aead.nonce_size()
+    }
+
+    /// `Nt`, the length of the ciphersuite's AEAD authentication tag
+    #[inline]
+    pub const fn nt(&self) -> usize {
+        self.aead.tag_size()
+    }
+}
+
+/// IANA registry of the ciphersuites defined by RFC9420
+///
+/// <https://www.rfc-editor.org/rfc/rfc9420.html#table-6>
+static CIPHERSUITE_REGISTRY: [CiphersuiteDescriptor; 7] = [
+    CiphersuiteDescriptor::new(
+        CiphersuiteId::MLS_128_DHKEMX25519_AES128GCM_SHA256_ED25519,
+        128,
+        HpkeKemId::DhKemX25519HkdfSha256,
+        HpkeKdfId::HkdfSha256,
+        HpkeAeadId::Aes128Gcm,
+        SignatureScheme::Ed25519,
+    ),
+    CiphersuiteDescriptor::new(
+        CiphersuiteId::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
+        128,
+        HpkeKemId::DhKemP256HkdfSha256,
+        HpkeKdfId::HkdfSha256,
+        HpkeAeadId::Aes128Gcm,
+        SignatureScheme::EcdsaSecp256r1Sha256,
+    ),
+    CiphersuiteDescriptor::new(
+        CiphersuiteId::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_ED25519,
+        128,
+        HpkeKemId::DhKemX25519HkdfSha256,
+        HpkeKdfId::HkdfSha256,
+        HpkeAeadId::ChaCha20Poly1305,
+        SignatureScheme::Ed25519,
+    ),
+    CiphersuiteDescriptor::new(
+        CiphersuiteId::MLS_256_DHKEMX448_AES256GCM_SHA512_ED448,
+        256,
+        HpkeKemId::DhKemX448HkdfSha512,
+        HpkeKdfId::HkdfSha512,
+        HpkeAeadId::Aes256Gcm,
+        SignatureScheme::Ed448,
+    ),
+    CiphersuiteDescriptor::new(
+        CiphersuiteId::MLS_256_DHKEMP521_AES256GCM_SHA512_P521,
+        256,
+        HpkeKemId::DhKemP521HkdfSha512,
+        HpkeKdfId::HkdfSha512,
+        HpkeAeadId::Aes256Gcm,
+        SignatureScheme::EcdsaSecp521r1Sha512,
+    ),
+    CiphersuiteDescriptor::new(
+        CiphersuiteId::MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_ED448,
+        256,
+        HpkeKemId::DhKemX448HkdfSha512,
+        HpkeKdfId::HkdfSha512,
+        HpkeAeadId::ChaCha20Poly1305,
+        SignatureScheme::Ed448,
+    ),
+    CiphersuiteDescriptor::new(
+        CiphersuiteId::MLS_256_DHKEMP384_AES256GCM_SHA384_P384,
+        256,
+        HpkeKemId::DhKemP384HkdfSha384,
+        HpkeKdfId::HkdfSha384,
+        HpkeAeadId::Aes256Gcm,
+        SignatureScheme::EcdsaSecp384r1Sha384,
+    ),
+];
+
+impl CiphersuiteId {
+    /// Returns the description of the ciphersuite's primitives.
+    ///
+    /// Returns `None` for values that aren't registered by RFC9420, such as the reserved value,
+    /// private-use values or GREASE values.
+    pub fn descriptor(&self) -> Option<&'static CiphersuiteDescriptor> {
+        CIPHERSUITE_REGISTRY
+            .iter()
+            .find(|descriptor| descriptor.ciphersuite == *self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_covers_spec_ciphersuites_only() {
+        for ciphersuite in CiphersuiteId::all_without_spec_default() {
+            let descriptor = ciphersuite.descriptor();
+            assert_eq!(
+                descriptor.map(|d| d.ciphersuite),
+                (*ciphersuite != CiphersuiteId::SPEC_RESERVED).then_some(ciphersuite)
+            );
+        }
+
+        for grease in crate::defs::GREASE_VALUES {
+            assert!(CiphersuiteId::new_unchecked(grease).descriptor().is_none());
+        }
+
+        let private_use = CiphersuiteId::new_private_use(0xF001).unwrap();
+        assert!(private_use.descriptor().is_none());
+    }
+}
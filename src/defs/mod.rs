@@ -1,8 +1,10 @@
 use crate::macros::impl_spec_enum;
 
+pub mod ciphersuite;
 pub mod labels;
 
 pub type LeafIndex = u32;
+pub type NodeIndex = u32;
 pub type SenderIndex = u32;
 pub type Generation = u32;
 pub type Epoch = u64;
@@ -1,7 +1,7 @@
 use crate::{
-    SensitiveBytes,
+    MlsSpecResult, SensitiveBytes,
     credential::Credential,
-    crypto::{SignaturePublicKey, SignaturePublicKeyRef},
+    crypto::{CipherSuiteProvider, Signable as _, SignaturePublicKey, SignaturePublicKeyRef},
     defs::CiphersuiteId,
 };
 
@@ -64,3 +64,41 @@ pub struct CredentialBindingTBS<'a> {
     pub credential_key: SignaturePublicKeyRef<'a>,
     pub signature_key: SignaturePublicKeyRef<'a>,
 }
+
+impl CredentialBinding {
+    /// Builds the CredentialBindingTBS binding this credential to the LeafNode's `signature_key`
+    pub fn to_tbs<'a>(
+        &'a self,
+        signature_key: SignaturePublicKeyRef<'a>,
+    ) -> CredentialBindingTBS<'a> {
+        CredentialBindingTBS {
+            cipher_suite: &self.cipher_suite,
+            credential: &self.credential,
+            credential_key: &self.credential_key,
+            signature_key,
+        }
+    }
+
+    /// Signs the binding with the private key matching `credential_key`
+    pub fn sign(
+        &mut self,
+        provider: &impl CipherSuiteProvider,
+        credential_private_key: &[u8],
+        signature_key: SignaturePublicKeyRef,
+    ) -> MlsSpecResult<()> {
+        self.signature = self
+            .to_tbs(signature_key)
+            .sign(provider, credential_private_key)?;
+        Ok(())
+    }
+
+    /// Verifies the binding signature using `credential_key`
+    pub fn verify(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        signature_key: SignaturePublicKeyRef,
+    ) -> MlsSpecResult<()> {
+        self.to_tbs(signature_key)
+            .verify(provider, &self.credential_key, &self.signature)
+    }
+}
@@ -123,9 +123,13 @@ pub struct KeyForExternalReceiverRef<'a> {
 pub mod messages {
     use crate::{
         SensitiveBytes,
+        crypto::CipherSuiteProvider,
         defs::Epoch,
         group::{GroupId, GroupIdRef, commits::Commit, proposals::Proposal},
-        messages::{ContentType, ContentTypeInner, FramedContentAuthData, PrivateMessageContent},
+        messages::{
+            ContentType, ContentTypeInner, FramedContentAuthData, PaddingPolicy,
+            PrivateMessageContent,
+        },
     };
 
     use super::{KeyForExternalReceiver, KeyForExternalReceiverRef};
@@ -156,7 +160,9 @@ pub mod messages {
 
     impl tls_codec::Size for SemiPrivateMessageContent {
         fn tls_serialized_len(&self) -> usize {
-            self.inner.tls_serialized_len() + self.auth.tls_serialized_len() + self.padding_len
+            self.inner.tls_serialized_content_len()
+                + self.auth.tls_serialized_len()
+                + self.padding_len
         }
     }
 
@@ -166,7 +172,7 @@ pub mod messages {
             writer: &mut W,
         ) -> Result<usize, tls_codec::Error> {
             let mut written = 0;
-            written += self.inner.tls_serialize(writer)?;
+            written += self.inner.tls_serialize_content(writer)?;
             written += self.auth.tls_serialize(writer)?;
             writer.write_all(&vec![0u8; self.padding_len][..])?;
             written += self.padding_len;
@@ -175,6 +181,27 @@ pub mod messages {
     }
 
     impl SemiPrivateMessageContent {
+        /// Builds the content of a SemiPrivateMessage, padded according to `padding`
+        pub fn new(
+            inner: ContentTypeInner,
+            auth: FramedContentAuthData,
+            padding: &PaddingPolicy,
+        ) -> Self {
+            use tls_codec::Size as _;
+            let plaintext_len = inner.tls_serialized_content_len() + auth.tls_serialized_len();
+            Self {
+                inner,
+                auth,
+                padding_len: padding.padding_len(plaintext_len),
+            }
+        }
+
+        /// Size of the `ciphertext` of the SemiPrivateMessage carrying this content
+        pub fn ciphertext_len(&self, provider: &impl CipherSuiteProvider) -> usize {
+            use tls_codec::Size as _;
+            self.tls_serialized_len() + provider.aead_tag_size()
+        }
+
         pub fn tls_deserialize_with_content_type<R: std::io::Read>(
             bytes: &mut R,
             content_type: ContentType,
@@ -229,3 +256,41 @@ pub mod messages {
         pub ciphertext: SensitiveBytes,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::messages::SemiPrivateMessageContent;
+    use crate::{
+        Serializable as _,
+        group::commits::Commit,
+        messages::{ContentType, ContentTypeInner, FramedContentAuthData},
+    };
+
+    #[test]
+    fn semiprivate_message_content_omits_content_type() {
+        // proposals<V> || optional<UpdatePath> || signature<V> || confirmation_tag<V> || padding
+        let bytes = [0x00, 0x00, 0x01, 0xAA, 0x02, 0x01, 0x02, 0x00];
+        let content = SemiPrivateMessageContent {
+            inner: ContentTypeInner::Commit {
+                commit: Commit {
+                    proposals: vec![],
+                    path: None,
+                },
+            },
+            auth: FramedContentAuthData {
+                signature: vec![0xAA].into(),
+                confirmation_tag: Some(vec![0x01, 0x02].into()),
+            },
+            padding_len: 1,
+        };
+        assert_eq!(content.to_tls_bytes().unwrap(), bytes);
+        assert_eq!(
+            SemiPrivateMessageContent::tls_deserialize_with_content_type(
+                &mut bytes.as_slice(),
+                ContentType::Commit,
+            )
+            .unwrap(),
+            content
+        );
+    }
+}
@@ -29,6 +29,188 @@ pub enum MlsSpecError {
     #[error("You have tried to use an invalid value spec-wise")]
     #[diagnostic(code(mls_spec::invalid_spec_value))]
     InvalidSpecValue,
+    #[error(
+        "Trying to build a LeafNodeTBS for an Update/Commit LeafNode but the member info hasn't been provided"
+    )]
+    #[diagnostic(code(mls_spec::missing_leaf_node_member_info))]
+    LeafNodeTBSMissingMemberInfo,
+    #[error("The ciphersuite {0:?} isn't supported by the crypto provider")]
+    #[diagnostic(code(mls_spec::unsupported_ciphersuite))]
+    UnsupportedCiphersuite(crate::defs::CiphersuiteId),
+    #[error("The signature couldn't be verified")]
+    #[diagnostic(code(mls_spec::invalid_signature))]
+    InvalidSignature,
+    #[error("The same PSK has been referenced more than once")]
+    #[diagnostic(code(mls_spec::duplicate_psk_id))]
+    DuplicatePskId,
+    #[error("The psk_nonce must be KDF.Nh ({expected}) bytes long, but is {actual} bytes long")]
+    #[diagnostic(code(mls_spec::invalid_psk_nonce_length))]
+    InvalidPskNonceLength { expected: usize, actual: usize },
+    #[error("A resumption PSK with usage {actual:?} cannot be used where {expected:?} is expected")]
+    #[diagnostic(code(mls_spec::invalid_resumption_psk_usage))]
+    InvalidResumptionPskUsage {
+        expected: crate::key_schedule::ResumptionPskUsage,
+        actual: crate::key_schedule::ResumptionPskUsage,
+    },
+    #[error("Exactly one resumption PSK with usage {usage:?} is expected, found {count}")]
+    #[diagnostic(code(mls_spec::invalid_resumption_psk_count))]
+    InvalidResumptionPskCount {
+        usage: crate::key_schedule::ResumptionPskUsage,
+        count: usize,
+    },
+    #[error("The content doesn't carry a confirmation tag, which is required for Commits")]
+    #[diagnostic(code(mls_spec::missing_confirmation_tag))]
+    MissingConfirmationTag,
+    #[error("The confirmation tag couldn't be verified")]
+    #[diagnostic(code(mls_spec::invalid_confirmation_tag))]
+    InvalidConfirmationTag,
+    #[error("The membership tag couldn't be verified")]
+    #[diagnostic(code(mls_spec::invalid_membership_tag))]
+    InvalidMembershipTag,
+    #[error("The membership key is required to protect or unprotect messages sent by members")]
+    #[diagnostic(code(mls_spec::missing_membership_key))]
+    MissingMembershipKey,
+    #[error("The PublicMessage is sent by a member but doesn't carry a membership tag")]
+    #[diagnostic(code(mls_spec::missing_membership_tag))]
+    MissingMembershipTag,
+    #[error("The PublicMessage carries a membership tag but isn't sent by a member")]
+    #[diagnostic(code(mls_spec::unexpected_membership_tag))]
+    UnexpectedMembershipTag,
+    #[error("Application data cannot be sent in a PublicMessage")]
+    #[diagnostic(code(mls_spec::public_message_application_data))]
+    PublicMessageApplicationData,
+    #[error("The message is meant for another group")]
+    #[diagnostic(code(mls_spec::message_group_id_mismatch))]
+    MessageGroupIdMismatch,
+    #[error("The message is meant for epoch {actual}, but the group is at epoch {expected}")]
+    #[diagnostic(code(mls_spec::message_epoch_mismatch))]
+    MessageEpochMismatch {
+        expected: crate::defs::Epoch,
+        actual: crate::defs::Epoch,
+    },
+    #[error("Only members can send PrivateMessages")]
+    #[diagnostic(code(mls_spec::invalid_private_message_sender))]
+    InvalidPrivateMessageSender,
+    #[error("The wire format {0:?} cannot be used here")]
+    #[diagnostic(code(mls_spec::unexpected_wire_format))]
+    UnexpectedWireFormat(crate::defs::WireFormat),
+    #[error("The KeyPackage version {actual} doesn't match the version {expected} of the group")]
+    #[diagnostic(code(mls_spec::key_package_version_mismatch))]
+    KeyPackageVersionMismatch {
+        expected: crate::defs::ProtocolVersion,
+        actual: crate::defs::ProtocolVersion,
+    },
+    #[error(
+        "The KeyPackage ciphersuite {actual:?} doesn't match the ciphersuite {expected:?} of the group"
+    )]
+    #[diagnostic(code(mls_spec::key_package_ciphersuite_mismatch))]
+    KeyPackageCiphersuiteMismatch {
+        expected: crate::defs::CiphersuiteId,
+        actual: crate::defs::CiphersuiteId,
+    },
+    #[error("The LeafNode of a KeyPackage must have a KeyPackage source, found {0}")]
+    #[diagnostic(code(mls_spec::invalid_key_package_leaf_node_source))]
+    InvalidKeyPackageLeafNodeSource(crate::tree::leaf_node::LeafNodeSourceType),
+    #[error("The KeyPackage lifetime {0:?} is either too long, not yet valid or expired")]
+    #[diagnostic(code(mls_spec::invalid_key_package_lifetime))]
+    InvalidKeyPackageLifetime(crate::group::KeyPackageLifetime),
+    #[error("The init_key of the KeyPackage is also the encryption_key of its LeafNode")]
+    #[diagnostic(code(mls_spec::key_package_init_key_reuse))]
+    KeyPackageInitKeyReuse,
+    #[error("The extension {0:?} is used but isn't listed in the LeafNode capabilities")]
+    #[diagnostic(code(mls_spec::unsupported_extension))]
+    UnsupportedExtension(crate::defs::ExtensionType),
+    #[error("The LeafNode signature couldn't be verified")]
+    #[diagnostic(code(mls_spec::invalid_leaf_node_signature))]
+    InvalidLeafNodeSignature,
+    #[error("The KeyPackage signature couldn't be verified")]
+    #[diagnostic(code(mls_spec::invalid_key_package_signature))]
+    InvalidKeyPackageSignature,
+    #[error("The tree hash of the ratchet tree doesn't match the one of the GroupContext")]
+    #[diagnostic(code(mls_spec::tree_hash_mismatch))]
+    TreeHashMismatch,
+    #[error("The parent node {0} isn't parent-hash valid")]
+    #[diagnostic(code(mls_spec::invalid_parent_hash))]
+    InvalidParentHash(crate::defs::NodeIndex),
+    #[error("The UpdatePath has {actual} nodes or ciphertexts where {expected} are expected")]
+    #[diagnostic(code(mls_spec::invalid_update_path_length))]
+    InvalidUpdatePathLength { expected: usize, actual: usize },
+    #[error("The public key of node {0} doesn't match the one derived from its path secret")]
+    #[diagnostic(code(mls_spec::update_path_key_mismatch))]
+    UpdatePathKeyMismatch(crate::defs::NodeIndex),
+    #[error(
+        "None of the path secrets of the UpdatePath can be decrypted with the known private keys"
+    )]
+    #[diagnostic(code(mls_spec::treekem_missing_private_key))]
+    TreeKemMissingPrivateKey,
+    #[error("A ratchet tree must have an odd number of nodes, found {0}")]
+    #[diagnostic(code(mls_spec::ratchet_tree_even_node_count))]
+    RatchetTreeEvenNodeCount(usize),
+    #[error("The ratchet tree isn't right-trimmed, i.e. it ends with a blank node")]
+    #[diagnostic(code(mls_spec::ratchet_tree_not_trimmed))]
+    RatchetTreeNotTrimmed,
+    #[error("The node {0} of the ratchet tree has the wrong type for its position")]
+    #[diagnostic(code(mls_spec::ratchet_tree_invalid_node_type))]
+    RatchetTreeInvalidNodeType(crate::defs::NodeIndex),
+    #[error("The leaf {leaf} isn't a valid unmerged leaf of the parent node {parent}")]
+    #[diagnostic(code(mls_spec::invalid_unmerged_leaf))]
+    InvalidUnmergedLeaf {
+        parent: crate::defs::NodeIndex,
+        leaf: crate::defs::LeafIndex,
+    },
+    #[error("The encryption key of node {0} is already used by another node")]
+    #[diagnostic(code(mls_spec::duplicate_encryption_key))]
+    DuplicateEncryptionKey(crate::defs::NodeIndex),
+    #[error("The signature key of leaf {0} is already used by another leaf")]
+    #[diagnostic(code(mls_spec::duplicate_signature_key))]
+    DuplicateSignatureKey(crate::defs::LeafIndex),
+    #[error("The leaf {0} doesn't support the capabilities required by the group")]
+    #[diagnostic(code(mls_spec::unsupported_required_capabilities))]
+    UnsupportedRequiredCapabilities(crate::defs::LeafIndex),
+    #[error(
+        "The leaf {leaf} doesn't support the credential type {credential_type:?} used in the group"
+    )]
+    #[diagnostic(code(mls_spec::unsupported_credential_type))]
+    UnsupportedCredentialType {
+        leaf: crate::defs::LeafIndex,
+        credential_type: crate::defs::CredentialType,
+    },
+    #[error("The leaf {0} is blank or outside of the ratchet tree")]
+    #[diagnostic(code(mls_spec::ratchet_tree_blank_leaf))]
+    RatchetTreeBlankLeaf(crate::defs::LeafIndex),
+    #[error("The node {0} is blank or outside of the ratchet tree")]
+    #[diagnostic(code(mls_spec::ratchet_tree_blank_node))]
+    RatchetTreeBlankNode(crate::defs::NodeIndex),
+    #[error("The ratchet tree diff changes node {index}, outside of its {node_count} nodes")]
+    #[diagnostic(code(mls_spec::ratchet_tree_diff_out_of_bounds))]
+    RatchetTreeDiffOutOfBounds {
+        index: crate::defs::NodeIndex,
+        node_count: u32,
+    },
+    #[error("The leaf index {0} is outside of the secret tree")]
+    #[diagnostic(code(mls_spec::secret_tree_leaf_out_of_bounds))]
+    SecretTreeLeafOutOfBounds(crate::defs::LeafIndex),
+    #[error("The key for generation {0} has already been consumed or deleted")]
+    #[diagnostic(code(mls_spec::secret_tree_generation_consumed))]
+    SecretTreeGenerationConsumed(crate::defs::Generation),
+    #[error("The generation {generation} is too far ahead of the current generation {current}")]
+    #[diagnostic(code(mls_spec::secret_tree_generation_too_far_ahead))]
+    SecretTreeGenerationTooFarAhead {
+        generation: crate::defs::Generation,
+        current: crate::defs::Generation,
+    },
+    #[error("The sender ratchet has run out of generations")]
+    #[diagnostic(code(mls_spec::secret_tree_generation_exhausted))]
+    SecretTreeGenerationExhausted,
+    #[error("The SenderData of the PrivateMessage couldn't be decrypted")]
+    #[diagnostic(code(mls_spec::sender_data_decryption_failed))]
+    SenderDataDecryptionFailed,
+    #[error("The content of the PrivateMessage couldn't be decrypted")]
+    #[diagnostic(code(mls_spec::private_message_decryption_failed))]
+    PrivateMessageDecryptionFailed,
+    #[error("The crypto provider encountered an error: {0}")]
+    #[diagnostic(code(mls_spec::crypto_provider_error))]
+    CryptoProviderError(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
     #[error(transparent)]
     #[diagnostic(code(mls_spec::tls_codec_error))]
     #[diagnostic_source]
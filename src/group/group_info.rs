@@ -1,6 +1,6 @@
 use crate::{
-    SensitiveBytes,
-    crypto::Mac,
+    MlsSpecResult, SensitiveBytes,
+    crypto::{CipherSuiteProvider, Mac, Signable as _, SignaturePublicKeyRef},
     defs::{LeafIndex, ProtocolVersion},
     group::extensions::{Extension, ExternalPub, RatchetTreeExtension},
     key_schedule::GroupContext,
@@ -45,6 +45,25 @@ impl GroupInfo {
         }
     }
 
+    /// Signs the GroupInfo with the private signature key of the `signer` member
+    pub fn sign(
+        &mut self,
+        provider: &impl CipherSuiteProvider,
+        signature_private_key: &[u8],
+    ) -> MlsSpecResult<()> {
+        self.signature = self.to_tbs().sign(provider, signature_private_key)?;
+        Ok(())
+    }
+
+    /// Verifies the GroupInfo signature using the signature key of the `signer` member
+    pub fn verify(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        signer_key: SignaturePublicKeyRef,
+    ) -> MlsSpecResult<()> {
+        self.to_tbs().verify(provider, signer_key, &self.signature)
+    }
+
     /// Returns the RatchetTree extension if present
     pub fn ratchet_tree(&self) -> Option<&RatchetTree> {
         self.extensions.iter().find_map(|ext| {
@@ -76,6 +76,13 @@ pub struct Welcome {
 }
 
 impl Welcome {
+    /// Finds the GroupSecrets encrypted for the KeyPackage identified by `key_package_ref`
+    pub fn secrets_for(&self, key_package_ref: &KeyPackageRef) -> Option<&EncryptedGroupSecrets> {
+        self.secrets
+            .iter()
+            .find(|secrets| &secrets.new_member == key_package_ref)
+    }
+
     pub fn into_mls_message(self, protocol_version: ProtocolVersion) -> MlsMessage {
         MlsMessage {
             version: protocol_version,
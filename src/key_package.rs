@@ -1,9 +1,11 @@
 use crate::{
-    SensitiveBytes,
-    defs::{CiphersuiteId, ProtocolVersion},
+    MlsSpecError, MlsSpecResult, SensitiveBytes, Serializable as _,
+    crypto::{CipherSuiteProvider, LabeledCrypto as _, Signable as _},
+    defs::{CiphersuiteId, ProtocolVersion, labels::HashReferenceKind},
     group::{KeyPackageRef, extensions::Extension},
+    key_schedule::GroupContext,
     messages::MlsMessage,
-    tree::leaf_node::LeafNode,
+    tree::leaf_node::{LeafNode, LeafNodeSource},
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, tls_codec::TlsSerialize, tls_codec::TlsSize)]
@@ -53,6 +55,96 @@ impl KeyPackage {
         }
     }
 
+    /// Signs the KeyPackage with the private key matching `leaf_node.signature_key`
+    pub fn sign(
+        &mut self,
+        provider: &impl CipherSuiteProvider,
+        signature_private_key: &[u8],
+    ) -> MlsSpecResult<()> {
+        self.signature = self.to_tbs().sign(provider, signature_private_key)?;
+        Ok(())
+    }
+
+    /// Verifies the KeyPackage signature using `leaf_node.signature_key`
+    pub fn verify_signature(&self, provider: &impl CipherSuiteProvider) -> MlsSpecResult<()> {
+        self.to_tbs()
+            .verify(provider, &self.leaf_node.signature_key, &self.signature)
+    }
+
+    /// Validates the KeyPackage of a member to be added to the group of `group_context`:
+    ///
+    /// - Its version and ciphersuite are the ones of the group
+    /// - Its LeafNode has a `key_package` source, with a lifetime that is acceptable and currently valid
+    /// - Its `init_key` differs from the `encryption_key` of its LeafNode
+    /// - The LeafNode capabilities list every non-default extension used by the KeyPackage or the LeafNode
+    /// - The signatures of the LeafNode and of the KeyPackage verify
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-10.1>
+    pub fn validate(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        group_context: &GroupContext,
+    ) -> MlsSpecResult<()> {
+        if self.version != group_context.version {
+            return Err(MlsSpecError::KeyPackageVersionMismatch {
+                expected: group_context.version,
+                actual: self.version,
+            });
+        }
+
+        if self.cipher_suite != group_context.cipher_suite {
+            return Err(MlsSpecError::KeyPackageCiphersuiteMismatch {
+                expected: group_context.cipher_suite,
+                actual: self.cipher_suite,
+            });
+        }
+
+        let LeafNodeSource::KeyPackage { lifetime } = &self.leaf_node.source else {
+            return Err(MlsSpecError::InvalidKeyPackageLeafNodeSource(
+                (&self.leaf_node.source).into(),
+            ));
+        };
+        if !lifetime.validate_range() || !lifetime.validate_expiration() {
+            return Err(MlsSpecError::InvalidKeyPackageLifetime(*lifetime));
+        }
+
+        if self.init_key == self.leaf_node.encryption_key {
+            return Err(MlsSpecError::KeyPackageInitKeyReuse);
+        }
+
+        let capabilities = &self.leaf_node.capabilities.extensions;
+        if let Some(unsupported) = self
+            .extensions
+            .iter()
+            .chain(&self.leaf_node.extensions)
+            .map(Extension::ext_type)
+            .find(|ext_type| !ext_type.is_spec_default() && !capabilities.contains(ext_type))
+        {
+            return Err(MlsSpecError::UnsupportedExtension(unsupported));
+        }
+
+        self.leaf_node
+            .verify_signature(provider, None)
+            .map_err(|e| match e {
+                MlsSpecError::InvalidSignature => MlsSpecError::InvalidLeafNodeSignature,
+                e => e,
+            })?;
+        self.verify_signature(provider).map_err(|e| match e {
+            MlsSpecError::InvalidSignature => MlsSpecError::InvalidKeyPackageSignature,
+            e => e,
+        })
+    }
+
+    /// Computes the `KeyPackageRef` of this KeyPackage
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-5.2-5>
+    pub fn key_package_ref(
+        &self,
+        provider: &impl CipherSuiteProvider,
+    ) -> MlsSpecResult<KeyPackageRef> {
+        provider.hash_reference(HashReferenceKind::KeyPackageRef, &self.to_tls_bytes()?)
+    }
+
     pub fn into_message(self) -> MlsMessage {
         MlsMessage {
             version: ProtocolVersion::default(),
@@ -67,3 +159,126 @@ pub struct KeyPackageWithRef {
     pub keypackage_ref: KeyPackageRef,
     pub keypackage: KeyPackage,
 }
+
+impl KeyPackageWithRef {
+    /// Wraps the KeyPackage along with its computed `KeyPackageRef`
+    pub fn new(keypackage: KeyPackage, provider: &impl CipherSuiteProvider) -> MlsSpecResult<Self> {
+        Ok(Self {
+            keypackage_ref: keypackage.key_package_ref(provider)?,
+            keypackage,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "rustcrypto"))]
+mod tests {
+    use super::*;
+    use crate::{
+        defs::ExtensionType, group::KeyPackageLifetime, test_utils::fixtures,
+        tree::leaf_node::LeafNodeSourceType,
+    };
+
+    /// Signed KeyPackage, which is valid for [fixtures::group_context]
+    fn key_package(provider: &impl CipherSuiteProvider) -> KeyPackage {
+        let (leaf_node, signature_private_key) = fixtures::leaf_node(provider, "a").unwrap();
+        let mut key_package = KeyPackage {
+            version: ProtocolVersion::default(),
+            cipher_suite: CiphersuiteId::default(),
+            init_key: provider.kem_generate().unwrap().extract_public_key(),
+            leaf_node,
+            extensions: vec![],
+            signature: vec![].into(),
+        };
+        key_package.sign(provider, &signature_private_key).unwrap();
+        key_package
+            .validate(provider, &fixtures::group_context())
+            .unwrap();
+        key_package
+    }
+
+    #[test]
+    fn validate_checks_group_ciphersuite() {
+        let provider = fixtures::provider();
+        let key_package = key_package(&provider);
+        let mut group_context = fixtures::group_context();
+        group_context.cipher_suite =
+            CiphersuiteId::new_unchecked(CiphersuiteId::default().wrapping_add(1));
+        assert!(matches!(
+            key_package.validate(&provider, &group_context),
+            Err(MlsSpecError::KeyPackageCiphersuiteMismatch { expected, actual })
+                if expected == group_context.cipher_suite && actual == key_package.cipher_suite
+        ));
+    }
+
+    #[test]
+    fn validate_checks_leaf_node_source() {
+        let provider = fixtures::provider();
+        let group_context = fixtures::group_context();
+        let mut key_package = key_package(&provider);
+
+        key_package.leaf_node.source = LeafNodeSource::Update;
+        assert!(matches!(
+            key_package.validate(&provider, &group_context),
+            Err(MlsSpecError::InvalidKeyPackageLeafNodeSource(
+                LeafNodeSourceType::Update
+            ))
+        ));
+
+        key_package.leaf_node.source = LeafNodeSource::KeyPackage {
+            lifetime: KeyPackageLifetime {
+                not_before: 0,
+                not_after: 1,
+            },
+        };
+        assert!(matches!(
+            key_package.validate(&provider, &group_context),
+            Err(MlsSpecError::InvalidKeyPackageLifetime(_))
+        ));
+    }
+
+    #[test]
+    fn validate_rejects_init_key_reuse() {
+        let provider = fixtures::provider();
+        let mut key_package = key_package(&provider);
+        key_package.init_key = key_package.leaf_node.encryption_key.clone();
+        assert!(matches!(
+            key_package.validate(&provider, &fixtures::group_context()),
+            Err(MlsSpecError::KeyPackageInitKeyReuse)
+        ));
+    }
+
+    #[test]
+    fn validate_rejects_unsupported_extensions() {
+        let provider = fixtures::provider();
+        let mut key_package = key_package(&provider);
+        key_package
+            .extensions
+            .push(Extension::new(0xF0F0, vec![]).unwrap());
+        assert!(matches!(
+            key_package.validate(&provider, &fixtures::group_context()),
+            Err(MlsSpecError::UnsupportedExtension(ext_type))
+                if ext_type == ExtensionType::new_unchecked(0xF0F0)
+        ));
+    }
+
+    #[test]
+    fn validate_reports_which_signature_is_invalid() {
+        let provider = fixtures::provider();
+        let group_context = fixtures::group_context();
+        let valid = key_package(&provider);
+
+        let mut key_package = valid.clone();
+        key_package.leaf_node.signature = b"tampered".to_vec().into();
+        assert!(matches!(
+            key_package.validate(&provider, &group_context),
+            Err(MlsSpecError::InvalidLeafNodeSignature)
+        ));
+
+        let mut key_package = valid;
+        key_package.signature = b"tampered".to_vec().into();
+        assert!(matches!(
+            key_package.validate(&provider, &group_context),
+            Err(MlsSpecError::InvalidKeyPackageSignature)
+        ));
+    }
+}
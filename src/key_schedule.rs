@@ -6,6 +6,13 @@ use crate::{
     tree::TreeHash,
 };
 
+mod epoch;
+pub use self::epoch::*;
+mod psk;
+mod secret_tree;
+pub use self::secret_tree::*;
+mod transcript_hash;
+
 #[derive(
     Debug,
     Clone,
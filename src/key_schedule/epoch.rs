@@ -0,0 +1,296 @@
+use crate::{
+    MlsSpecResult, SensitiveBytes,
+    crypto::{CipherSuiteProvider, HpkeKeyPair, LabeledCrypto as _},
+    defs::labels::KdfLabelKind,
+    key_schedule::EpochSecretExport,
+};
+
+/// Output of the key schedule for a given epoch
+///
+/// <https://www.rfc-editor.org/rfc/rfc9420.html#section-8>
+#[derive(Clone, zeroize::Zeroize, zeroize::ZeroizeOnDrop)]
+pub struct EpochSecrets {
+    pub joiner_secret: SensitiveBytes,
+    pub welcome_secret: SensitiveBytes,
+    pub epoch_secret: SensitiveBytes,
+    pub sender_data_secret: SensitiveBytes,
+    pub encryption_secret: SensitiveBytes,
+    pub exporter_secret: SensitiveBytes,
+    pub external_secret: SensitiveBytes,
+    pub confirmation_key: SensitiveBytes,
+    pub membership_key: SensitiveBytes,
+    pub resumption_psk: SensitiveBytes,
+    pub epoch_authenticator: SensitiveBytes,
+    #[cfg(feature = "draft-kohbrok-mls-associated-parties")]
+    pub associated_parties_secret: SensitiveBytes,
+    /// `init_secret` of the *next* epoch
+    pub init_secret: SensitiveBytes,
+}
+
+impl std::fmt::Debug for EpochSecrets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EpochSecrets").finish_non_exhaustive()
+    }
+}
+
+impl EpochSecrets {
+    /// Runs the key schedule from the previous epoch's `init_secret`
+    ///
+    /// A missing `commit_secret` or `psk_secret` is replaced by a zero vector of length `KDF.Nh`.
+    ///
+    /// `group_context` is the TLS-serialized [crate::key_schedule::GroupContext] of the new epoch
+    pub fn derive(
+        provider: &impl CipherSuiteProvider,
+        init_secret: &[u8],
+        commit_secret: Option<&[u8]>,
+        psk_secret: Option<&[u8]>,
+        group_context: &[u8],
+    ) -> MlsSpecResult<Self> {
+        let zero = vec![0u8; provider.kdf_extract_size()];
+        let prk = provider.kdf_extract(init_secret, commit_secret.unwrap_or(&zero))?;
+        let joiner_secret = provider.expand_with_label(
+            prk.as_slice(),
+            &KdfLabelKind::Joiner,
+            group_context,
+            provider.kdf_extract_size() as u16,
+        )?;
+
+        Self::from_joiner_secret(provider, joiner_secret, psk_secret, group_context)
+    }
+
+    /// Runs the key schedule from a `joiner_secret`, i.e. when joining a group through a Welcome
+    pub fn from_joiner_secret(
+        provider: &impl CipherSuiteProvider,
+        joiner_secret: SensitiveBytes,
+        psk_secret: Option<&[u8]>,
+        group_context: &[u8],
+    ) -> MlsSpecResult<Self> {
+        let member_secret = Self::member_secret(provider, &joiner_secret, psk_secret)?;
+        let welcome_secret =
+            provider.derive_secret(member_secret.as_slice(), &KdfLabelKind::Welcome)?;
+        let epoch_secret = provider.expand_with_label(
+            member_secret.as_slice(),
+            &KdfLabelKind::Epoch,
+            group_context,
+            provider.kdf_extract_size() as u16,
+        )?;
+
+        let derive = |export: EpochSecretExport| {
+            provider.derive_secret(epoch_secret.as_slice(), &export.into())
+        };
+
+        Ok(Self {
+            joiner_secret,
+            welcome_secret,
+            sender_data_secret: derive(EpochSecretExport::SenderDataSecret)?,
+            encryption_secret: derive(EpochSecretExport::EncryptionSecret)?,
+            exporter_secret: derive(EpochSecretExport::ExporterSecret)?,
+            external_secret: derive(EpochSecretExport::ExternalSecret)?,
+            confirmation_key: derive(EpochSecretExport::ConfirmationKey)?,
+            membership_key: derive(EpochSecretExport::MembershipKey)?,
+            resumption_psk: derive(EpochSecretExport::ResumptionPsk)?,
+            epoch_authenticator: derive(EpochSecretExport::EpochAuthenticator)?,
+            #[cfg(feature = "draft-kohbrok-mls-associated-parties")]
+            associated_parties_secret: derive(EpochSecretExport::AssociatedPartiesSecret)?,
+            init_secret: provider.derive_secret(epoch_secret.as_slice(), &KdfLabelKind::Init)?,
+            epoch_secret,
+        })
+    }
+
+    /// Derives the `welcome_secret` alone, which is needed by joiners to decrypt the GroupInfo
+    /// before the GroupContext of the epoch is known
+    pub fn derive_welcome_secret(
+        provider: &impl CipherSuiteProvider,
+        joiner_secret: &[u8],
+        psk_secret: Option<&[u8]>,
+    ) -> MlsSpecResult<SensitiveBytes> {
+        let member_secret = Self::member_secret(provider, joiner_secret, psk_secret)?;
+        provider.derive_secret(member_secret.as_slice(), &KdfLabelKind::Welcome)
+    }
+
+    /// `KDF.Extract(joiner_secret, psk_secret)`
+    fn member_secret(
+        provider: &impl CipherSuiteProvider,
+        joiner_secret: &[u8],
+        psk_secret: Option<&[u8]>,
+    ) -> MlsSpecResult<SensitiveBytes> {
+        let zero = vec![0u8; provider.kdf_extract_size()];
+        provider.kdf_extract(joiner_secret, psk_secret.unwrap_or(&zero))
+    }
+
+    /// Returns the secret exported by the key schedule for `export`
+    pub fn export(&self, export: EpochSecretExport) -> &SensitiveBytes {
+        match export {
+            EpochSecretExport::SenderDataSecret => &self.sender_data_secret,
+            EpochSecretExport::EncryptionSecret => &self.encryption_secret,
+            EpochSecretExport::ExporterSecret => &self.exporter_secret,
+            EpochSecretExport::ExternalSecret => &self.external_secret,
+            EpochSecretExport::ConfirmationKey => &self.confirmation_key,
+            EpochSecretExport::MembershipKey => &self.membership_key,
+            EpochSecretExport::ResumptionPsk => &self.resumption_psk,
+            EpochSecretExport::EpochAuthenticator => &self.epoch_authenticator,
+            #[cfg(feature = "draft-kohbrok-mls-associated-parties")]
+            EpochSecretExport::AssociatedPartiesSecret => &self.associated_parties_secret,
+        }
+    }
+
+    /// `MLS-Exporter(Label, Context, Length)`, allowing applications to derive secrets from the group
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-8.5>
+    pub fn mls_exporter(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        label: &str,
+        context: &[u8],
+        length: u16,
+    ) -> MlsSpecResult<SensitiveBytes> {
+        let derived_secret = provider.derive_secret(
+            self.exporter_secret.as_slice(),
+            &KdfLabelKind::Arbitrary(label.to_string()),
+        )?;
+        provider.expand_with_label(
+            derived_secret.as_slice(),
+            &KdfLabelKind::Exported,
+            provider.hash(context)?.as_slice(),
+            length,
+        )
+    }
+
+    /// Exports a secret scoped to a Safe Application component.
+    ///
+    /// The `application_export_secret` is derived from the `epoch_secret`, then bound to the component
+    /// through a `ComponentOperationLabel` before being exported like with `MLS-Exporter`:
+    ///
+    /// ```text
+    /// application_export_secret = DeriveSecret(epoch_secret, "application_export")
+    /// component_secret = ExpandWithLabel(application_export_secret, "application_export",
+    ///                                    ComponentOperationLabel, KDF.Nh)
+    /// exported = ExpandWithLabel(component_secret, "exported", Hash(context), length)
+    /// ```
+    #[cfg(feature = "draft-ietf-mls-extensions")]
+    pub fn application_export(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        component_id: crate::drafts::mls_extensions::safe_application::ComponentId,
+        label: &[u8],
+        context: &[u8],
+        length: u16,
+    ) -> MlsSpecResult<SensitiveBytes> {
+        use crate::{
+            Serializable as _,
+            drafts::mls_extensions::safe_application::{
+                ComponentOperationBaseLabel, ComponentOperationLabel,
+            },
+        };
+
+        let application_export_secret = provider.derive_secret(
+            self.epoch_secret.as_slice(),
+            &KdfLabelKind::ApplicationExportSecret,
+        )?;
+        let operation_label = ComponentOperationLabel {
+            base_label: ComponentOperationBaseLabel::Application,
+            component_id,
+            label: label.to_vec(),
+        }
+        .to_tls_bytes()?;
+        let component_secret = provider.expand_with_label(
+            application_export_secret.as_slice(),
+            &KdfLabelKind::ApplicationExportSecret,
+            &operation_label,
+            provider.kdf_extract_size() as u16,
+        )?;
+        provider.expand_with_label(
+            component_secret.as_slice(),
+            &KdfLabelKind::Exported,
+            provider.hash(context)?.as_slice(),
+            length,
+        )
+    }
+
+    /// Derives the external key pair of the epoch from `external_secret`
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-8.3>
+    pub fn external_key_pair(
+        &self,
+        provider: &impl CipherSuiteProvider,
+    ) -> MlsSpecResult<HpkeKeyPair> {
+        provider.kem_derive(self.external_secret.as_slice())
+    }
+}
+
+#[cfg(all(test, feature = "rustcrypto", feature = "draft-ietf-mls-extensions"))]
+mod tests {
+    use super::*;
+    use crate::test_utils::fixtures;
+
+    /// Spells out every `KDFLabel` fed to `KDF.Expand` by `application_export`, so that a change in
+    /// the `ComponentOperationLabel` framing can't go unnoticed.
+    ///
+    /// <https://www.ietf.org/archive/id/draft-ietf-mls-extensions-08.html#name-exported-secrets>
+    #[test]
+    fn application_export_label_encoding() {
+        let provider = fixtures::provider();
+        assert_eq!(provider.kdf_extract_size(), 32);
+        let secrets =
+            EpochSecrets::derive(&provider, &[0x33; 32], None, None, b"group context").unwrap();
+
+        // DeriveSecret(epoch_secret, "application_export")
+        let info = [
+            &[0x00, 0x20][..],
+            &[26],
+            b"MLS 1.0 application_export",
+            &[0],
+        ]
+        .concat();
+        let application_export_secret = provider
+            .kdf_expand(secrets.epoch_secret.as_slice(), &info, 32)
+            .unwrap();
+
+        // ComponentOperationLabel { "Application", component_id, label }
+        let operation_label = [
+            &[11][..],
+            b"Application",
+            &[0x12, 0x34, 0x56, 0x78],
+            &[5],
+            b"label",
+        ]
+        .concat();
+        // ExpandWithLabel(application_export_secret, "application_export", ComponentOperationLabel, KDF.Nh)
+        let info = [
+            &[0x00, 0x20][..],
+            &[26],
+            b"MLS 1.0 application_export",
+            &[22],
+            &operation_label,
+        ]
+        .concat();
+        let component_secret = provider
+            .kdf_expand(application_export_secret.as_slice(), &info, 32)
+            .unwrap();
+
+        // ExpandWithLabel(component_secret, "exported", Hash(context), length)
+        let context_hash = provider.hash(b"context").unwrap();
+        let info = [
+            &[0x00, 0x2A][..],
+            &[16],
+            b"MLS 1.0 exported",
+            &[32],
+            context_hash.as_slice(),
+        ]
+        .concat();
+        let expected = provider
+            .kdf_expand(component_secret.as_slice(), &info, 42)
+            .unwrap();
+
+        let exported = secrets
+            .application_export(&provider, 0x1234_5678, b"label", b"context", 42)
+            .unwrap();
+        assert_eq!(exported, expected);
+
+        // The component is part of the derivation
+        let other_component = secrets
+            .application_export(&provider, 0x1234_5679, b"label", b"context", 42)
+            .unwrap();
+        assert_ne!(other_component, exported);
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{
+    MlsSpecError, MlsSpecResult, SensitiveBytes, Serializable as _,
+    crypto::{CipherSuiteProvider, LabeledCrypto as _, PreSharedKeyPair},
+    defs::labels::KdfLabelKind,
+    key_schedule::{PreSharedKeyId, PreSharedKeyIdPskType, PskLabel, ResumptionPskUsage},
+};
+
+impl PreSharedKeyId {
+    /// Checks that the `psk_nonce` is `KDF.Nh` bytes long
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-8.4-11>
+    pub fn validate_nonce(&self, provider: &impl CipherSuiteProvider) -> MlsSpecResult<()> {
+        let expected = provider.kdf_extract_size();
+        let actual = self.psk_nonce.as_slice().len();
+        if actual != expected {
+            return Err(MlsSpecError::InvalidPskNonceLength { expected, actual });
+        }
+
+        Ok(())
+    }
+
+    /// Validates a list of PSKs referenced by a Commit or a Welcome
+    ///
+    /// `context` is the `ResumptionPskUsage` allowed by the context the PSKs are used in:
+    /// - [ResumptionPskUsage::Application] for regular Commits and Welcomes, in which all resumption PSKs must have this usage
+    /// - [ResumptionPskUsage::ReInit] or [ResumptionPskUsage::Branch] for the Welcome of a reinitialized or branched group,
+    ///   which must contain exactly one resumption PSK with this usage
+    ///
+    /// PSKs are duplicates when they have the same type and identifier, whatever their nonce. Application PSKs
+    /// (draft-ietf-mls-extensions) are identified by their `component_id` along with their `psk_id`,
+    /// and can't use the reserved component ID 0.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-12.2-4>
+    pub fn validate_list<'a>(
+        provider: &impl CipherSuiteProvider,
+        psk_ids: impl IntoIterator<Item = &'a Self>,
+        context: ResumptionPskUsage,
+    ) -> MlsSpecResult<()> {
+        if context == ResumptionPskUsage::Reserved {
+            return Err(MlsSpecError::ReservedValueUsage);
+        }
+
+        let mut seen: Vec<&PreSharedKeyIdPskType> = vec![];
+        let mut resumption_psks = 0usize;
+        for psk_id in psk_ids {
+            psk_id.validate_nonce(provider)?;
+
+            if seen.contains(&&psk_id.psktype) {
+                return Err(MlsSpecError::DuplicatePskId);
+            }
+            seen.push(&psk_id.psktype);
+
+            match &psk_id.psktype {
+                PreSharedKeyIdPskType::External(_) => {}
+                PreSharedKeyIdPskType::Resumption(resumption) => {
+                    if resumption.usage != context {
+                        return Err(MlsSpecError::InvalidResumptionPskUsage {
+                            expected: context,
+                            actual: resumption.usage,
+                        });
+                    }
+                    resumption_psks += 1;
+                }
+                #[cfg(feature = "draft-ietf-mls-extensions")]
+                PreSharedKeyIdPskType::Application(application) => {
+                    if application.component_id == 0 {
+                        return Err(MlsSpecError::ReservedValueUsage);
+                    }
+                }
+            }
+        }
+
+        if context != ResumptionPskUsage::Application && resumption_psks != 1 {
+            return Err(MlsSpecError::InvalidResumptionPskCount {
+                usage: context,
+                count: resumption_psks,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl PreSharedKeyPair {
+    /// Validates the PSK list (see [PreSharedKeyId::validate_list]), then computes the `psk_secret` to be
+    /// injected in the key schedule.
+    ///
+    /// An empty list results in a zero vector of length `KDF.Nh`.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-8.4>
+    pub fn psk_secret(
+        provider: &impl CipherSuiteProvider,
+        psks: &[Self],
+        context: ResumptionPskUsage,
+    ) -> MlsSpecResult<SensitiveBytes> {
+        PreSharedKeyId::validate_list(provider, psks.iter().map(|psk| &psk.psk_id), context)?;
+
+        let nh = provider.kdf_extract_size();
+        let zero = vec![0u8; nh];
+        let count = u16::try_from(psks.len()).map_err(|_| MlsSpecError::InvalidSpecValue)?;
+
+        let mut psk_secret: SensitiveBytes = zero.clone().into();
+        for (index, psk) in (0..count).zip(psks) {
+            let psk_extracted = provider.kdf_extract(&zero, psk.psk_secret.as_slice())?;
+            let psk_label = PskLabel {
+                id: &psk.psk_id,
+                index,
+                count,
+            }
+            .to_tls_bytes()?;
+            let psk_input = provider.expand_with_label(
+                psk_extracted.as_slice(),
+                &KdfLabelKind::DerivedPsk,
+                &psk_label,
+                nh as u16,
+            )?;
+            psk_secret = provider.kdf_extract(psk_input.as_slice(), psk_secret.as_slice())?;
+        }
+
+        Ok(psk_secret)
+    }
+}
+
+#[cfg(all(test, feature = "rustcrypto"))]
+mod tests {
+    use super::*;
+    use crate::{
+        key_schedule::{ExternalPsk, ResumptionPsk},
+        test_utils::fixtures,
+    };
+
+    fn external(psk_id: &[u8], nonce_len: usize) -> PreSharedKeyId {
+        PreSharedKeyId {
+            psktype: PreSharedKeyIdPskType::External(ExternalPsk {
+                psk_id: psk_id.to_vec(),
+            }),
+            psk_nonce: vec![0; nonce_len].into(),
+        }
+    }
+
+    fn resumption(usage: ResumptionPskUsage, nonce_len: usize) -> PreSharedKeyId {
+        PreSharedKeyId {
+            psktype: PreSharedKeyIdPskType::Resumption(ResumptionPsk {
+                usage,
+                psk_group_id: b"group".to_vec(),
+                psk_epoch: 1,
+            }),
+            psk_nonce: vec![0; nonce_len].into(),
+        }
+    }
+
+    #[test]
+    fn validate_list_rejects_duplicates_and_bad_nonces() {
+        let provider = fixtures::provider();
+        let nh = provider.kdf_extract_size();
+        let context = ResumptionPskUsage::Application;
+
+        PreSharedKeyId::validate_list(&provider, [], context).unwrap();
+        PreSharedKeyId::validate_list(
+            &provider,
+            &[external(b"a", nh), external(b"b", nh)],
+            context,
+        )
+        .unwrap();
+
+        // Referencing the same PSK twice is forbidden, even with a different nonce
+        let mut duplicate = external(b"a", nh);
+        duplicate.psk_nonce = vec![0xFF; nh].into();
+        assert!(matches!(
+            PreSharedKeyId::validate_list(&provider, &[external(b"a", nh), duplicate], context),
+            Err(MlsSpecError::DuplicatePskId)
+        ));
+
+        assert!(matches!(
+            PreSharedKeyId::validate_list(&provider, &[external(b"a", nh - 1)], context),
+            Err(MlsSpecError::InvalidPskNonceLength { expected, actual })
+                if expected == nh && actual == nh - 1
+        ));
+    }
+
+    #[test]
+    fn validate_list_checks_resumption_usage() {
+        let provider = fixtures::provider();
+        let nh = provider.kdf_extract_size();
+        let psks = [
+            external(b"a", nh),
+            resumption(ResumptionPskUsage::ReInit, nh),
+        ];
+
+        // Resumption PSKs must match the usage of the context they're used in
+        assert!(matches!(
+            PreSharedKeyId::validate_list(&provider, &psks, ResumptionPskUsage::Application),
+            Err(MlsSpecError::InvalidResumptionPskUsage {
+                expected: ResumptionPskUsage::Application,
+                actual: ResumptionPskUsage::ReInit,
+            })
+        ));
+        PreSharedKeyId::validate_list(&provider, &psks, ResumptionPskUsage::ReInit).unwrap();
+        assert!(matches!(
+            PreSharedKeyId::validate_list(&provider, &psks[..1], ResumptionPskUsage::Branch),
+            Err(MlsSpecError::InvalidResumptionPskCount { count: 0, .. })
+        ));
+        assert!(matches!(
+            PreSharedKeyId::validate_list(&provider, [], ResumptionPskUsage::Reserved),
+            Err(MlsSpecError::ReservedValueUsage)
+        ));
+    }
+
+    #[test]
+    fn empty_psk_secret_is_zero() {
+        let provider = fixtures::provider();
+        let psk_secret =
+            PreSharedKeyPair::psk_secret(&provider, &[], ResumptionPskUsage::Application).unwrap();
+        assert_eq!(psk_secret.as_slice(), vec![0; provider.kdf_extract_size()]);
+    }
+
+    #[cfg(feature = "draft-ietf-mls-extensions")]
+    #[test]
+    fn psk_secret_of_application_psks() {
+        use crate::key_schedule::ApplicationPsk;
+
+        let provider = fixtures::provider();
+        let nh = provider.kdf_extract_size();
+        let application = |component_id, psk_id: &[u8]| PreSharedKeyPair {
+            psk_id: PreSharedKeyId {
+                psktype: PreSharedKeyIdPskType::Application(ApplicationPsk {
+                    component_id,
+                    psk_id: psk_id.to_vec(),
+                }),
+                psk_nonce: vec![0; nh].into(),
+            },
+            psk_secret: vec![0x42; nh].into(),
+        };
+        let psk_secret = |psks: &[PreSharedKeyPair]| {
+            PreSharedKeyPair::psk_secret(&provider, psks, ResumptionPskUsage::Application)
+        };
+
+        // The same psk_id can be used by different components, and PSKs are bound to their type
+        let valid = psk_secret(&[application(1, b"a"), application(2, b"a")]).unwrap();
+        assert_ne!(
+            valid,
+            psk_secret(&[application(2, b"a"), application(1, b"a")]).unwrap()
+        );
+        let external = PreSharedKeyPair {
+            psk_id: external(b"a", nh),
+            psk_secret: vec![0x42; nh].into(),
+        };
+        assert_ne!(
+            psk_secret(&[application(1, b"a")]).unwrap(),
+            psk_secret(&[external]).unwrap()
+        );
+
+        assert!(matches!(
+            psk_secret(&[application(1, b"a"), application(1, b"a")]),
+            Err(MlsSpecError::DuplicatePskId)
+        ));
+        assert!(matches!(
+            psk_secret(&[application(0, b"a")]),
+            Err(MlsSpecError::ReservedValueUsage)
+        ));
+    }
+}
@@ -0,0 +1,348 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::{
+    MlsSpecError, MlsSpecResult, SensitiveBytes,
+    crypto::{CipherSuiteProvider, LabeledCrypto as _},
+    defs::{Generation, LeafIndex, NodeIndex, labels::KdfLabelKind},
+    tree::math,
+};
+
+/// Kind of content a sender ratchet is used for
+///
+/// <https://www.rfc-editor.org/rfc/rfc9420.html#section-9.1>
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum RatchetKind {
+    Handshake,
+    Application,
+}
+
+impl From<RatchetKind> for KdfLabelKind {
+    fn from(value: RatchetKind) -> Self {
+        match value {
+            RatchetKind::Handshake => KdfLabelKind::Handshake,
+            RatchetKind::Application => KdfLabelKind::Application,
+        }
+    }
+}
+
+/// AEAD key and nonce of a given generation of a sender ratchet
+#[derive(Debug, Clone, PartialEq, Eq, zeroize::Zeroize, zeroize::ZeroizeOnDrop)]
+pub struct RatchetKeyNonce {
+    #[zeroize(skip)]
+    pub generation: Generation,
+    pub key: SensitiveBytes,
+    pub nonce: SensitiveBytes,
+}
+
+/// Parameters controlling how much the sender ratchets tolerate out-of-order messages
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SecretTreeConfig {
+    /// Maximum number of skipped key/nonce pairs retained per ratchet.
+    /// When exceeded, the oldest ones are deleted.
+    pub out_of_order_tolerance: u32,
+    /// Maximum number of generations a ratchet can be advanced by a single message
+    pub maximum_forward_distance: u32,
+}
+
+impl Default for SecretTreeConfig {
+    fn default() -> Self {
+        Self {
+            out_of_order_tolerance: 5,
+            maximum_forward_distance: 1000,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HashRatchet {
+    secret: SensitiveBytes,
+    generation: Generation,
+    skipped: VecDeque<RatchetKeyNonce>,
+}
+
+impl HashRatchet {
+    fn key_nonce(
+        &self,
+        provider: &impl CipherSuiteProvider,
+    ) -> MlsSpecResult<(RatchetKeyNonce, SensitiveBytes)> {
+        let secret = self.secret.as_slice();
+        let key_nonce = RatchetKeyNonce {
+            generation: self.generation,
+            key: provider.derive_tree_secret(
+                secret,
+                &KdfLabelKind::Key,
+                self.generation,
+                provider.aead_key_size() as u16,
+            )?,
+            nonce: provider.derive_tree_secret(
+                secret,
+                &KdfLabelKind::Nonce,
+                self.generation,
+                provider.aead_nonce_size() as u16,
+            )?,
+        };
+        let next_secret = provider.derive_tree_secret(
+            secret,
+            &KdfLabelKind::Secret,
+            self.generation,
+            provider.kdf_extract_size() as u16,
+        )?;
+
+        Ok((key_nonce, next_secret))
+    }
+
+    /// Returns the key/nonce of the current generation, then ratchets forward, deleting the previous secret
+    fn advance(&mut self, provider: &impl CipherSuiteProvider) -> MlsSpecResult<RatchetKeyNonce> {
+        let next_generation = self
+            .generation
+            .checked_add(1)
+            .ok_or(MlsSpecError::SecretTreeGenerationExhausted)?;
+        let (key_nonce, next_secret) = self.key_nonce(provider)?;
+        self.secret = next_secret;
+        self.generation = next_generation;
+        Ok(key_nonce)
+    }
+
+    /// Calls `f` with the key/nonce of `generation`, only deleting it and ratcheting forward when `f` succeeds
+    fn get_with<T>(
+        &mut self,
+        provider: &impl CipherSuiteProvider,
+        generation: Generation,
+        config: &SecretTreeConfig,
+        f: impl FnOnce(&RatchetKeyNonce) -> MlsSpecResult<T>,
+    ) -> MlsSpecResult<T> {
+        if generation < self.generation {
+            let position = self
+                .skipped
+                .iter()
+                .position(|key_nonce| key_nonce.generation == generation)
+                .ok_or(MlsSpecError::SecretTreeGenerationConsumed(generation))?;
+            let output = f(&self.skipped[position])?;
+            // The key is removed from the ratchet once used, so it can't be reused
+            self.skipped.remove(position);
+            return Ok(output);
+        }
+
+        if generation - self.generation > config.maximum_forward_distance {
+            return Err(MlsSpecError::SecretTreeGenerationTooFarAhead {
+                generation,
+                current: self.generation,
+            });
+        }
+
+        let mut ratchet = self.clone();
+        while ratchet.generation < generation {
+            let skipped = ratchet.advance(provider)?;
+            ratchet.skipped.push_back(skipped);
+            while ratchet.skipped.len() > config.out_of_order_tolerance as usize {
+                ratchet.skipped.pop_front();
+            }
+        }
+
+        let output = f(&ratchet.advance(provider)?)?;
+        *self = ratchet;
+        Ok(output)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SenderRatchets {
+    handshake: HashRatchet,
+    application: HashRatchet,
+}
+
+impl SenderRatchets {
+    fn ratchet_mut(&mut self, kind: RatchetKind) -> &mut HashRatchet {
+        match kind {
+            RatchetKind::Handshake => &mut self.handshake,
+            RatchetKind::Application => &mut self.application,
+        }
+    }
+}
+
+/// Secret tree of an epoch, from which the per-sender handshake and application ratchets are derived.
+///
+/// Secrets are deleted (and zeroized) as soon as they're not needed anymore: parent node secrets once
+/// their children are derived, ratchet secrets once they've been ratcheted forward, and key/nonce pairs once
+/// they've been handed over.
+///
+/// <https://www.rfc-editor.org/rfc/rfc9420.html#section-9>
+#[derive(Debug, Clone)]
+pub struct SecretTree {
+    leaf_count: u32,
+    config: SecretTreeConfig,
+    node_secrets: BTreeMap<NodeIndex, SensitiveBytes>,
+    ratchets: BTreeMap<LeafIndex, SenderRatchets>,
+}
+
+impl SecretTree {
+    /// Creates the secret tree of an epoch from its `encryption_secret`, for a ratchet tree having `leaf_count` leaves
+    pub fn new(
+        encryption_secret: SensitiveBytes,
+        leaf_count: u32,
+        config: SecretTreeConfig,
+    ) -> Self {
+        let leaf_count = leaf_count.max(1).next_power_of_two();
+        let mut node_secrets = BTreeMap::new();
+        node_secrets.insert(math::root(leaf_count), encryption_secret);
+        Self {
+            leaf_count,
+            config,
+            node_secrets,
+            ratchets: BTreeMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &SecretTreeConfig {
+        &self.config
+    }
+
+    /// Returns the key/nonce used by `leaf` to encrypt its next message of the given `kind`,
+    /// and ratchets forward
+    pub fn next_key_nonce(
+        &mut self,
+        provider: &impl CipherSuiteProvider,
+        leaf: LeafIndex,
+        kind: RatchetKind,
+    ) -> MlsSpecResult<RatchetKeyNonce> {
+        self.sender_ratchets(provider, leaf)?
+            .ratchet_mut(kind)
+            .advance(provider)
+    }
+
+    /// Returns the key/nonce used by `leaf` to encrypt the message of the given `kind` and `generation`.
+    ///
+    /// Generations that are skipped over are retained according to the [SecretTreeConfig],
+    /// and a key/nonce pair can only be obtained once.
+    pub fn key_nonce(
+        &mut self,
+        provider: &impl CipherSuiteProvider,
+        leaf: LeafIndex,
+        kind: RatchetKind,
+        generation: Generation,
+    ) -> MlsSpecResult<RatchetKeyNonce> {
+        self.with_key_nonce(provider, leaf, kind, generation, |key_nonce| {
+            Ok(key_nonce.clone())
+        })
+    }
+
+    /// Calls `f` with the key/nonce used by `leaf` to encrypt the message of the given `kind` and `generation`.
+    ///
+    /// Unlike [Self::key_nonce], the key/nonce is only deleted, and the ratchet only advanced, when `f` succeeds,
+    /// so that a message failing to decrypt doesn't prevent the genuine one from being decrypted.
+    pub fn with_key_nonce<T>(
+        &mut self,
+        provider: &impl CipherSuiteProvider,
+        leaf: LeafIndex,
+        kind: RatchetKind,
+        generation: Generation,
+        f: impl FnOnce(&RatchetKeyNonce) -> MlsSpecResult<T>,
+    ) -> MlsSpecResult<T> {
+        let config = self.config;
+        self.sender_ratchets(provider, leaf)?
+            .ratchet_mut(kind)
+            .get_with(provider, generation, &config, f)
+    }
+
+    fn sender_ratchets(
+        &mut self,
+        provider: &impl CipherSuiteProvider,
+        leaf: LeafIndex,
+    ) -> MlsSpecResult<&mut SenderRatchets> {
+        if leaf >= self.leaf_count {
+            return Err(MlsSpecError::SecretTreeLeafOutOfBounds(leaf));
+        }
+
+        if !self.ratchets.contains_key(&leaf) {
+            let leaf_secret = self.leaf_secret(provider, leaf)?;
+            let ratchet = |kind: RatchetKind| -> MlsSpecResult<HashRatchet> {
+                Ok(HashRatchet {
+                    secret: provider.expand_with_label(
+                        leaf_secret.as_slice(),
+                        &kind.into(),
+                        &[],
+                        provider.kdf_extract_size() as u16,
+                    )?,
+                    generation: 0,
+                    skipped: VecDeque::new(),
+                })
+            };
+            let ratchets = SenderRatchets {
+                handshake: ratchet(RatchetKind::Handshake)?,
+                application: ratchet(RatchetKind::Application)?,
+            };
+            self.ratchets.insert(leaf, ratchets);
+        }
+
+        Ok(self.ratchets.get_mut(&leaf).unwrap())
+    }
+
+    /// Derives the secret of `leaf` from its closest ancestor whose secret is still retained,
+    /// deleting every consumed node secret along the way
+    fn leaf_secret(
+        &mut self,
+        provider: &impl CipherSuiteProvider,
+        leaf: LeafIndex,
+    ) -> MlsSpecResult<SensitiveBytes> {
+        let leaf_node = math::leaf_to_node(leaf);
+        let path = std::iter::once(leaf_node)
+            .chain(math::direct_path(leaf_node, self.leaf_count))
+            .collect::<Vec<_>>();
+        let retained = path
+            .iter()
+            .position(|node| self.node_secrets.contains_key(node))
+            .ok_or(MlsSpecError::SecretTreeLeafOutOfBounds(leaf))?;
+
+        for node in path[1..=retained].iter().rev() {
+            let secret = self.node_secrets.remove(node).unwrap();
+            for (child, context) in [
+                (math::left(*node), b"left".as_slice()),
+                (math::right(*node), b"right".as_slice()),
+            ] {
+                let Some(child) = child else {
+                    continue;
+                };
+                let child_secret = provider.expand_with_label(
+                    secret.as_slice(),
+                    &KdfLabelKind::Tree,
+                    context,
+                    provider.kdf_extract_size() as u16,
+                )?;
+                self.node_secrets.insert(child, child_secret);
+            }
+        }
+
+        Ok(self.node_secrets.remove(&leaf_node).unwrap())
+    }
+}
+
+#[cfg(all(test, feature = "rustcrypto"))]
+mod tests {
+    use super::*;
+    use crate::test_utils::fixtures;
+
+    #[test]
+    fn failed_key_nonce_use_keeps_ratchet_state() {
+        let provider = fixtures::provider();
+        let mut tree = SecretTree::new(vec![0; 32].into(), 2, SecretTreeConfig::default());
+        let fail = |_: &RatchetKeyNonce| Err::<(), _>(MlsSpecError::PrivateMessageDecryptionFailed);
+        let kind = RatchetKind::Application;
+
+        assert!(tree.with_key_nonce(&provider, 1, kind, 10, fail).is_err());
+        assert_eq!(tree.ratchets[&1].application.generation, 0);
+        assert_eq!(
+            tree.key_nonce(&provider, 1, kind, 10).unwrap().generation,
+            10
+        );
+
+        assert!(tree.with_key_nonce(&provider, 1, kind, 9, fail).is_err());
+        assert_eq!(tree.key_nonce(&provider, 1, kind, 9).unwrap().generation, 9);
+        assert!(matches!(
+            tree.key_nonce(&provider, 1, kind, 9),
+            Err(MlsSpecError::SecretTreeGenerationConsumed(9))
+        ));
+    }
+}
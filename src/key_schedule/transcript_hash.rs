@@ -0,0 +1,67 @@
+use crate::{
+    MlsSpecError, MlsSpecResult, Serializable as _,
+    crypto::CipherSuiteProvider,
+    key_schedule::{
+        ConfirmedTranscriptHashInput, GroupContext, InterimTranscriptHashInput, TranscriptHash,
+    },
+    messages::AuthenticatedContent,
+};
+
+impl ConfirmedTranscriptHashInput<'_> {
+    /// `confirmed_transcript_hash_[epoch] = Hash(interim_transcript_hash_[epoch - 1] || ConfirmedTranscriptHashInput_[epoch])`
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-8.2>
+    pub fn hash(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        interim_transcript_hash: &[u8],
+    ) -> MlsSpecResult<TranscriptHash> {
+        let input = [interim_transcript_hash, &self.to_tls_bytes()?].concat();
+        provider.hash(&input)
+    }
+}
+
+impl InterimTranscriptHashInput<'_> {
+    /// `interim_transcript_hash_[epoch] = Hash(confirmed_transcript_hash_[epoch] || InterimTranscriptHashInput_[epoch])`
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-8.2>
+    pub fn hash(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        confirmed_transcript_hash: &[u8],
+    ) -> MlsSpecResult<TranscriptHash> {
+        let input = [confirmed_transcript_hash, &self.to_tls_bytes()?].concat();
+        provider.hash(&input)
+    }
+}
+
+impl GroupContext {
+    /// Advances the transcript with the Commit carried by `commit`, given the interim transcript hash
+    /// of the previous epoch.
+    ///
+    /// The new confirmed transcript hash is set on this GroupContext, and the new interim transcript hash is returned.
+    ///
+    /// Returns [MlsSpecError::MissingConfirmationTag] if `commit` doesn't carry a confirmation tag,
+    /// which is the case of anything that isn't a Commit.
+    pub fn update_transcript_hashes(
+        &mut self,
+        provider: &impl CipherSuiteProvider,
+        commit: &AuthenticatedContent,
+        interim_transcript_hash: &[u8],
+    ) -> MlsSpecResult<TranscriptHash> {
+        let confirmation_tag = commit
+            .auth
+            .confirmation_tag
+            .as_ref()
+            .ok_or(MlsSpecError::MissingConfirmationTag)?;
+
+        let confirmed_transcript_hash = commit
+            .confirmed_transcript_hash_input()
+            .hash(provider, interim_transcript_hash)?;
+        let interim_transcript_hash = InterimTranscriptHashInput::from(confirmation_tag.as_slice())
+            .hash(provider, confirmed_transcript_hash.as_slice())?;
+
+        self.confirmed_transcript_hash = confirmed_transcript_hash;
+        Ok(interim_transcript_hash)
+    }
+}
@@ -8,6 +8,8 @@ mod message_kinds;
 pub use self::message_kinds::*;
 mod content_encryption;
 pub use self::content_encryption::*;
+mod padding;
+pub use self::padding::*;
 
 use crate::defs::ProtocolVersion;
 
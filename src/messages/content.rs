@@ -2,9 +2,9 @@ use tls_codec::Deserialize;
 
 use crate::{
     MlsSpecError, MlsSpecResult, SensitiveBytes,
-    crypto::Mac,
-    defs::{Epoch, ProposalType, ProtocolVersion, WireFormat},
-    group::{GroupId, group_info::GroupInfo, welcome::Welcome},
+    crypto::{CipherSuiteProvider, LabeledCrypto as _, Mac, Signable as _},
+    defs::{Epoch, ProposalType, ProtocolVersion, WireFormat, labels::HashReferenceKind},
+    group::{GroupId, ProposalRef, group_info::GroupInfo, welcome::Welcome},
     key_package::KeyPackage,
     key_schedule::{ConfirmedTranscriptHashInput, GroupContext},
     messages::{ContentType, ContentTypeInner, PrivateMessage, PublicMessage, Sender, SenderType},
@@ -382,6 +382,99 @@ impl AuthenticatedContent {
             auth: &self.auth,
         }
     }
+
+    /// Signs `content` with `SignWithLabel(., "FramedContentTBS", FramedContentTBS)`.
+    ///
+    /// The confirmation tag of Commits must be set afterwards with [Self::set_confirmation_tag],
+    /// as it depends on the signature through the confirmed transcript hash.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-6.1>
+    pub fn new_signed(
+        provider: &impl CipherSuiteProvider,
+        wire_format: WireFormat,
+        content: FramedContent,
+        signature_private_key: &[u8],
+        ctx: &GroupContext,
+    ) -> MlsSpecResult<Self> {
+        let signature = content
+            .to_tbs(&wire_format, ctx)?
+            .sign(provider, signature_private_key)?;
+
+        Ok(Self {
+            wire_format,
+            content,
+            auth: FramedContentAuthData {
+                signature,
+                confirmation_tag: None,
+            },
+        })
+    }
+
+    /// Verifies the signature of the content with the sender's signature public key
+    pub fn verify_signature(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        signature_public_key: &[u8],
+        ctx: &GroupContext,
+    ) -> MlsSpecResult<()> {
+        self.content.to_tbs(&self.wire_format, ctx)?.verify(
+            provider,
+            signature_public_key,
+            &self.auth.signature,
+        )
+    }
+
+    /// Sets the confirmation tag of a Commit, which is `MAC(confirmation_key, confirmed_transcript_hash)`,
+    /// both being those of the epoch the Commit creates
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-6.1-11>
+    pub fn set_confirmation_tag(
+        &mut self,
+        provider: &impl CipherSuiteProvider,
+        confirmation_key: &[u8],
+        confirmed_transcript_hash: &[u8],
+    ) -> MlsSpecResult<()> {
+        if !matches!(self.content.content, ContentTypeInner::Commit { .. }) {
+            return Err(MlsSpecError::InvalidContentType);
+        }
+
+        self.auth.confirmation_tag =
+            Some(provider.mac(confirmation_key, confirmed_transcript_hash)?);
+        Ok(())
+    }
+
+    /// Verifies, in constant time, the confirmation tag of a Commit
+    pub fn verify_confirmation_tag(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        confirmation_key: &[u8],
+        confirmed_transcript_hash: &[u8],
+    ) -> MlsSpecResult<()> {
+        let confirmation_tag = self
+            .auth
+            .confirmation_tag
+            .as_ref()
+            .ok_or(MlsSpecError::MissingConfirmationTag)?;
+        let expected = provider.mac(confirmation_key, confirmed_transcript_hash)?;
+        if !confirmation_tag.ct_eq_slice(expected.as_slice()) {
+            return Err(MlsSpecError::InvalidConfirmationTag);
+        }
+
+        Ok(())
+    }
+
+    /// Computes the `ProposalRef` of the Proposal carried by this AuthenticatedContent
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-5.2-6>
+    pub fn proposal_ref(&self, provider: &impl CipherSuiteProvider) -> MlsSpecResult<ProposalRef> {
+        use crate::Serializable as _;
+
+        if !matches!(self.content.content, ContentTypeInner::Proposal { .. }) {
+            return Err(MlsSpecError::InvalidContentType);
+        }
+
+        provider.hash_reference(HashReferenceKind::ProposalRef, &self.to_tls_bytes()?)
+    }
 }
 
 impl tls_codec::Deserialize for AuthenticatedContent {
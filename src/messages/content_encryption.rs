@@ -1,6 +1,12 @@
 use crate::{
-    defs::{Epoch, Generation, LeafIndex},
-    messages::{ContentType, ContentTypeInner, FramedContentAuthData},
+    MlsSpecError, MlsSpecResult, Parsable as _, SensitiveBytes, Serializable as _,
+    crypto::{CipherSuiteProvider, LabeledCrypto as _},
+    defs::{Epoch, Generation, LeafIndex, WireFormat, labels::KdfLabelKind},
+    key_schedule::{GroupContext, RatchetKind, SecretTree},
+    messages::{
+        AuthenticatedContent, ContentType, ContentTypeInner, FramedContent, FramedContentAuthData,
+        PaddingPolicy, PrivateMessage, Sender,
+    },
 };
 
 pub type ReuseGuard = [u8; 4];
@@ -21,14 +27,14 @@ impl Eq for PrivateMessageContent {}
 
 impl tls_codec::Size for PrivateMessageContent {
     fn tls_serialized_len(&self) -> usize {
-        self.inner.tls_serialized_len() + self.auth.tls_serialized_len() + self.padding_len
+        self.inner.tls_serialized_content_len() + self.auth.tls_serialized_len() + self.padding_len
     }
 }
 
 impl tls_codec::Serialize for PrivateMessageContent {
     fn tls_serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<usize, tls_codec::Error> {
         let mut written = 0;
-        written += self.inner.tls_serialize(writer)?;
+        written += self.inner.tls_serialize_content(writer)?;
         written += self.auth.tls_serialize(writer)?;
         writer.write_all(&vec![0u8; self.padding_len][..])?;
         written += self.padding_len;
@@ -37,6 +43,27 @@ impl tls_codec::Serialize for PrivateMessageContent {
 }
 
 impl PrivateMessageContent {
+    /// Builds the content of a PrivateMessage, padded according to `padding`
+    pub fn new(
+        inner: ContentTypeInner,
+        auth: FramedContentAuthData,
+        padding: &PaddingPolicy,
+    ) -> Self {
+        use tls_codec::Size as _;
+        let plaintext_len = inner.tls_serialized_content_len() + auth.tls_serialized_len();
+        Self {
+            inner,
+            auth,
+            padding_len: padding.padding_len(plaintext_len),
+        }
+    }
+
+    /// Size of the `ciphertext` of the PrivateMessage carrying this content
+    pub fn ciphertext_len(&self, provider: &impl CipherSuiteProvider) -> usize {
+        use tls_codec::Size as _;
+        self.tls_serialized_len() + provider.aead_tag_size()
+    }
+
     pub(crate) fn consume_padding<R: std::io::Read>(
         bytes: &mut R,
     ) -> Result<usize, tls_codec::Error> {
@@ -160,7 +187,440 @@ pub struct SenderData {
 /// ````
 #[derive(Debug, Clone, PartialEq, Eq, tls_codec::TlsSerialize, tls_codec::TlsSize)]
 pub struct SenderDataAAD<'a> {
+    #[tls_codec(with = "crate::tlspl::bytes")]
     pub group_id: &'a [u8],
     pub epoch: &'a Epoch,
     pub content_type: &'a ContentType,
 }
+
+impl SenderData {
+    /// Derives the `sender_data_key` and `sender_data_nonce` from the `sender_data_secret`
+    /// and a sample of the PrivateMessage `ciphertext`
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-6.3.2-3>
+    pub fn key_nonce(
+        provider: &impl CipherSuiteProvider,
+        sender_data_secret: &[u8],
+        ciphertext: &[u8],
+    ) -> MlsSpecResult<(SensitiveBytes, SensitiveBytes)> {
+        let sample = &ciphertext[..ciphertext.len().min(provider.kdf_extract_size())];
+        let key = provider.expand_with_label(
+            sender_data_secret,
+            &KdfLabelKind::Key,
+            sample,
+            provider.aead_key_size() as u16,
+        )?;
+        let nonce = provider.expand_with_label(
+            sender_data_secret,
+            &KdfLabelKind::Nonce,
+            sample,
+            provider.aead_nonce_size() as u16,
+        )?;
+        Ok((key, nonce))
+    }
+}
+
+fn ratchet_kind(content_type: ContentType) -> MlsSpecResult<RatchetKind> {
+    match content_type {
+        ContentType::Reserved => Err(MlsSpecError::InvalidContentType),
+        ContentType::Application => Ok(RatchetKind::Application),
+        _ => Ok(RatchetKind::Handshake),
+    }
+}
+
+/// XORs the first bytes of the ratchet nonce with the `reuse_guard`
+fn apply_reuse_guard(nonce: &SensitiveBytes, reuse_guard: &ReuseGuard) -> Vec<u8> {
+    let mut nonce = nonce.as_slice().to_vec();
+    nonce
+        .iter_mut()
+        .zip(reuse_guard)
+        .for_each(|(byte, guard)| *byte ^= guard);
+    nonce
+}
+
+impl PrivateMessage {
+    const WIRE_FORMAT: WireFormat = WireFormat::new_unchecked(WireFormat::MLS_PRIVATE_MESSAGE);
+
+    /// Encrypts a signed [AuthenticatedContent] sent by a member into a PrivateMessage,
+    /// using the next key/nonce of the sender's ratchet in `secret_tree`.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-6.3>
+    pub fn encrypt(
+        provider: &impl CipherSuiteProvider,
+        authenticated_content: &AuthenticatedContent,
+        secret_tree: &mut SecretTree,
+        sender_data_secret: &[u8],
+        padding: &PaddingPolicy,
+    ) -> MlsSpecResult<Self> {
+        if authenticated_content.wire_format != Self::WIRE_FORMAT {
+            return Err(MlsSpecError::UnexpectedWireFormat(
+                authenticated_content.wire_format,
+            ));
+        }
+
+        let content = &authenticated_content.content;
+        let Sender::Member(leaf_index) = content.sender else {
+            return Err(MlsSpecError::InvalidPrivateMessageSender);
+        };
+        let content_type: ContentType = (&content.content).into();
+        if content_type == ContentType::Commit
+            && authenticated_content.auth.confirmation_tag.is_none()
+        {
+            return Err(MlsSpecError::MissingConfirmationTag);
+        }
+
+        let key_nonce =
+            secret_tree.next_key_nonce(provider, leaf_index, ratchet_kind(content_type)?)?;
+        let mut reuse_guard = ReuseGuard::default();
+        provider.random_bytes(&mut reuse_guard)?;
+
+        let plaintext = PrivateMessageContent::new(
+            content.content.clone(),
+            authenticated_content.auth.clone(),
+            padding,
+        )
+        .to_tls_bytes()?;
+        let aad = PrivateContentAAD {
+            group_id: &content.group_id,
+            epoch: &content.epoch,
+            content_type: &content_type,
+            authenticated_data: content.authenticated_data.as_slice(),
+        }
+        .to_tls_bytes()?;
+        let ciphertext = provider.aead_seal(
+            key_nonce.key.as_slice(),
+            &apply_reuse_guard(&key_nonce.nonce, &reuse_guard),
+            &aad,
+            &plaintext,
+        )?;
+
+        let sender_data = SenderData {
+            leaf_index,
+            generation: key_nonce.generation,
+            reuse_guard,
+        }
+        .to_tls_bytes()?;
+        let sender_data_aad = SenderDataAAD {
+            group_id: &content.group_id,
+            epoch: &content.epoch,
+            content_type: &content_type,
+        }
+        .to_tls_bytes()?;
+        let (sender_data_key, sender_data_nonce) =
+            SenderData::key_nonce(provider, sender_data_secret, &ciphertext)?;
+        let encrypted_sender_data = provider.aead_seal(
+            sender_data_key.as_slice(),
+            sender_data_nonce.as_slice(),
+            &sender_data_aad,
+            &sender_data,
+        )?;
+
+        Ok(Self {
+            group_id: content.group_id.clone(),
+            epoch: content.epoch,
+            content_type,
+            authenticated_data: content.authenticated_data.clone(),
+            encrypted_sender_data: encrypted_sender_data.into(),
+            ciphertext: ciphertext.into(),
+        })
+    }
+
+    /// Decrypts the [SenderData] of this PrivateMessage
+    pub fn decrypt_sender_data(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        sender_data_secret: &[u8],
+    ) -> MlsSpecResult<SenderData> {
+        let (sender_data_key, sender_data_nonce) =
+            SenderData::key_nonce(provider, sender_data_secret, self.ciphertext.as_slice())?;
+        let sender_data_aad = SenderDataAAD {
+            group_id: &self.group_id,
+            epoch: &self.epoch,
+            content_type: &self.content_type,
+        }
+        .to_tls_bytes()?;
+        let sender_data = provider
+            .aead_open(
+                sender_data_key.as_slice(),
+                sender_data_nonce.as_slice(),
+                &sender_data_aad,
+                self.encrypted_sender_data.as_slice(),
+            )
+            .map_err(|_| MlsSpecError::SenderDataDecryptionFailed)?;
+        SenderData::from_tls_bytes(sender_data.as_slice())
+    }
+
+    /// Checks that this PrivateMessage belongs to the group and epoch of `ctx`, then decrypts it,
+    /// consuming the matching key/nonce of the sender's ratchet in `secret_tree`.
+    ///
+    /// The ratchet is left untouched when the ciphertext fails to authenticate.
+    /// The signature of the returned [AuthenticatedContent] still has to be verified with the sender's signature key.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-6.3>
+    pub fn decrypt(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        ctx: &GroupContext,
+        secret_tree: &mut SecretTree,
+        sender_data_secret: &[u8],
+    ) -> MlsSpecResult<AuthenticatedContent> {
+        if self.group_id.as_slice() != ctx.group_id() {
+            return Err(MlsSpecError::MessageGroupIdMismatch);
+        }
+        if self.epoch != ctx.epoch {
+            return Err(MlsSpecError::MessageEpochMismatch {
+                expected: ctx.epoch,
+                actual: self.epoch,
+            });
+        }
+
+        let sender_data = self.decrypt_sender_data(provider, sender_data_secret)?;
+        let aad = PrivateContentAAD {
+            group_id: &self.group_id,
+            epoch: &self.epoch,
+            content_type: &self.content_type,
+            authenticated_data: self.authenticated_data.as_slice(),
+        }
+        .to_tls_bytes()?;
+        let plaintext = secret_tree.with_key_nonce(
+            provider,
+            sender_data.leaf_index,
+            ratchet_kind(self.content_type)?,
+            sender_data.generation,
+            |key_nonce| {
+                provider
+                    .aead_open(
+                        key_nonce.key.as_slice(),
+                        &apply_reuse_guard(&key_nonce.nonce, &sender_data.reuse_guard),
+                        &aad,
+                        self.ciphertext.as_slice(),
+                    )
+                    .map_err(|_| MlsSpecError::PrivateMessageDecryptionFailed)
+            },
+        )?;
+        let content = PrivateMessageContent::tls_deserialize_with_content_type(
+            &mut plaintext.as_slice(),
+            self.content_type,
+        )?;
+
+        Ok(AuthenticatedContent {
+            wire_format: Self::WIRE_FORMAT,
+            content: FramedContent {
+                group_id: self.group_id.clone(),
+                epoch: self.epoch,
+                sender: Sender::Member(sender_data.leaf_index),
+                authenticated_data: self.authenticated_data.clone(),
+                content: content.inner,
+            },
+            auth: content.auth,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "rustcrypto"))]
+mod tests {
+    use super::*;
+    use crate::{group::commits::Commit, key_schedule::SecretTreeConfig, test_utils::fixtures};
+
+    const SENDER_DATA_SECRET: &[u8] = &[0x11; 32];
+
+    fn secret_tree() -> SecretTree {
+        SecretTree::new(vec![0x22; 32].into(), 2, SecretTreeConfig::default())
+    }
+
+    /// Signed application message sent by `sender`
+    fn application(provider: &impl CipherSuiteProvider, sender: Sender) -> AuthenticatedContent {
+        let ctx = fixtures::group_context();
+        let mut signature_key_pair = provider.signature_key_generate().unwrap();
+        AuthenticatedContent::new_signed(
+            provider,
+            PrivateMessage::WIRE_FORMAT,
+            FramedContent {
+                group_id: ctx.group_id().to_vec(),
+                epoch: ctx.epoch,
+                sender,
+                authenticated_data: vec![].into(),
+                content: ContentTypeInner::Application {
+                    application_data: b"hi".to_vec(),
+                },
+            },
+            &signature_key_pair.extract_secret_key(),
+            &ctx,
+        )
+        .unwrap()
+    }
+
+    fn encrypt(
+        provider: &impl CipherSuiteProvider,
+        content: &AuthenticatedContent,
+    ) -> MlsSpecResult<PrivateMessage> {
+        PrivateMessage::encrypt(
+            provider,
+            content,
+            &mut secret_tree(),
+            SENDER_DATA_SECRET,
+            &PaddingPolicy::None,
+        )
+    }
+
+    #[test]
+    fn private_message_content_omits_content_type() {
+        // application_data<V> || signature<V> || padding
+        let bytes = [0x02, b'h', b'i', 0x03, 0xAA, 0xBB, 0xCC, 0x00, 0x00];
+        let content = PrivateMessageContent {
+            inner: ContentTypeInner::Application {
+                application_data: b"hi".to_vec(),
+            },
+            auth: FramedContentAuthData {
+                signature: vec![0xAA, 0xBB, 0xCC].into(),
+                confirmation_tag: None,
+            },
+            padding_len: 2,
+        };
+        assert_eq!(content.to_tls_bytes().unwrap(), bytes);
+        let decoded = PrivateMessageContent::tls_deserialize_with_content_type(
+            &mut bytes.as_slice(),
+            ContentType::Application,
+        )
+        .unwrap();
+        assert_eq!(decoded, content);
+        assert_eq!(decoded.padding_len, 2);
+
+        // proposals<V> || optional<UpdatePath> || signature<V> || confirmation_tag<V>
+        let bytes = [0x00, 0x00, 0x01, 0xAA, 0x02, 0x01, 0x02];
+        let content = PrivateMessageContent {
+            inner: ContentTypeInner::Commit {
+                commit: Commit {
+                    proposals: vec![],
+                    path: None,
+                },
+            },
+            auth: FramedContentAuthData {
+                signature: vec![0xAA].into(),
+                confirmation_tag: Some(vec![0x01, 0x02].into()),
+            },
+            padding_len: 0,
+        };
+        assert_eq!(content.to_tls_bytes().unwrap(), bytes);
+        assert_eq!(
+            PrivateMessageContent::tls_deserialize_with_content_type(
+                &mut bytes.as_slice(),
+                ContentType::Commit,
+            )
+            .unwrap(),
+            content
+        );
+    }
+
+    #[test]
+    fn sender_data_aad_prefixes_group_id() {
+        // group_id<V> || epoch || content_type
+        let bytes = [
+            0x02, b'g', b'1', 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x01,
+        ];
+        let aad = SenderDataAAD {
+            group_id: b"g1",
+            epoch: &5,
+            content_type: &ContentType::Application,
+        };
+        assert_eq!(aad.to_tls_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn encrypt_rejects_non_member_senders() {
+        let provider = fixtures::provider();
+        let content = application(&provider, Sender::NewMemberProposal);
+        assert!(matches!(
+            encrypt(&provider, &content),
+            Err(MlsSpecError::InvalidPrivateMessageSender)
+        ));
+    }
+
+    #[test]
+    fn decrypt_rejects_replayed_messages() {
+        let provider = fixtures::provider();
+        let content = application(&provider, Sender::Member(1));
+        let message = encrypt(&provider, &content).unwrap();
+
+        let mut receiver_tree = secret_tree();
+        let decrypted = message
+            .decrypt(
+                &provider,
+                &fixtures::group_context(),
+                &mut receiver_tree,
+                SENDER_DATA_SECRET,
+            )
+            .unwrap();
+        assert_eq!(decrypted, content);
+        assert!(matches!(
+            message.decrypt(
+                &provider,
+                &fixtures::group_context(),
+                &mut receiver_tree,
+                SENDER_DATA_SECRET
+            ),
+            Err(MlsSpecError::SecretTreeGenerationConsumed(0))
+        ));
+    }
+
+    #[test]
+    fn tampered_message_keeps_key_of_genuine_one() {
+        let provider = fixtures::provider();
+        let content = application(&provider, Sender::Member(1));
+        let message = encrypt(&provider, &content).unwrap();
+
+        let mut receiver_tree = secret_tree();
+        let mut tampered = message.clone();
+        tampered.authenticated_data = b"tampered".to_vec().into();
+        assert!(matches!(
+            tampered.decrypt(
+                &provider,
+                &fixtures::group_context(),
+                &mut receiver_tree,
+                SENDER_DATA_SECRET
+            ),
+            Err(MlsSpecError::PrivateMessageDecryptionFailed)
+        ));
+
+        let decrypted = message
+            .decrypt(
+                &provider,
+                &fixtures::group_context(),
+                &mut receiver_tree,
+                SENDER_DATA_SECRET,
+            )
+            .unwrap();
+        assert_eq!(decrypted, content);
+    }
+
+    #[test]
+    fn decrypt_checks_group_and_epoch() {
+        let provider = fixtures::provider();
+        let ctx = fixtures::group_context();
+        let content = application(&provider, Sender::Member(1));
+        let message = encrypt(&provider, &content).unwrap();
+
+        // Checked before any key is derived, even from a wrong sender_data_secret
+        let mut receiver_tree = secret_tree();
+        let mut other_group = GroupContext::with_group_id(b"other group".to_vec());
+        other_group.cipher_suite = ctx.cipher_suite;
+        assert!(matches!(
+            message.decrypt(&provider, &other_group, &mut receiver_tree, &[0; 32]),
+            Err(MlsSpecError::MessageGroupIdMismatch)
+        ));
+        let mut next_epoch = ctx.clone();
+        next_epoch.epoch += 1;
+        assert!(matches!(
+            message.decrypt(&provider, &next_epoch, &mut receiver_tree, &[0; 32]),
+            Err(MlsSpecError::MessageEpochMismatch {
+                expected: 1,
+                actual: 0
+            })
+        ));
+
+        let decrypted = message
+            .decrypt(&provider, &ctx, &mut receiver_tree, SENDER_DATA_SECRET)
+            .unwrap();
+        assert_eq!(decrypted, content);
+    }
+}
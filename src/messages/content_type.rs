@@ -84,3 +84,29 @@ impl From<&ContentTypeInner> for ContentType {
         }
     }
 }
+
+impl ContentTypeInner {
+    /// Size of the selected content alone, i.e. without the [ContentType] discriminant,
+    /// as carried by `PrivateMessageContent` where the type is implied by the outer message
+    pub(crate) fn tls_serialized_content_len(&self) -> usize {
+        use tls_codec::Size as _;
+        self.tls_serialized_len() - ContentType::Reserved.tls_serialized_len()
+    }
+
+    /// Serializes the selected content alone, i.e. without the [ContentType] discriminant
+    pub(crate) fn tls_serialize_content<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> Result<usize, tls_codec::Error> {
+        use tls_codec::Serialize as _;
+        match self {
+            Self::Application { application_data } => {
+                crate::tlspl::bytes::tls_serialize(application_data, writer)
+            }
+            Self::Proposal { proposal } => proposal.tls_serialize(writer),
+            Self::Commit { commit } => commit.tls_serialize(writer),
+            #[cfg(feature = "draft-mularczyk-mls-splitcommit")]
+            Self::SplitCommit { split_commit } => split_commit.tls_serialize(writer),
+        }
+    }
+}
@@ -1,9 +1,13 @@
 use crate::{
-    SensitiveBytes,
-    crypto::Mac,
+    MlsSpecError, MlsSpecResult, SensitiveBytes, Serializable as _,
+    crypto::{CipherSuiteProvider, Mac},
     defs::WireFormat,
     group::GroupId,
-    messages::{ContentType, FramedContent, FramedContentAuthData, FramedContentTBS, Sender},
+    key_schedule::GroupContext,
+    messages::{
+        ContentType, ContentTypeInner, FramedContent, FramedContentAuthData, FramedContentTBS,
+        Sender,
+    },
 };
 
 use super::{AuthenticatedContent, AuthenticatedContentRef};
@@ -56,6 +60,120 @@ impl PublicMessage {
             auth: &self.auth,
         }
     }
+
+    /// Wraps a signed (and for Commits, confirmed) [AuthenticatedContent] in a PublicMessage,
+    /// computing the `membership_tag` when the sender is a member.
+    ///
+    /// `membership_key` is only required for member senders.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-6.2>
+    pub fn protect(
+        provider: &impl CipherSuiteProvider,
+        authenticated_content: AuthenticatedContent,
+        ctx: &GroupContext,
+        membership_key: Option<&[u8]>,
+    ) -> MlsSpecResult<Self> {
+        if authenticated_content.wire_format != Self::AUTH_CONTENT_REF_WF {
+            return Err(MlsSpecError::UnexpectedWireFormat(
+                authenticated_content.wire_format,
+            ));
+        }
+
+        match authenticated_content.content.content {
+            ContentTypeInner::Application { .. } => {
+                return Err(MlsSpecError::PublicMessageApplicationData);
+            }
+            ContentTypeInner::Commit { .. }
+                if authenticated_content.auth.confirmation_tag.is_none() =>
+            {
+                return Err(MlsSpecError::MissingConfirmationTag);
+            }
+            _ => {}
+        }
+
+        let mut message = Self {
+            content: authenticated_content.content,
+            auth: authenticated_content.auth,
+            membership_tag: None,
+        };
+
+        if matches!(message.content.sender, Sender::Member(_)) {
+            let membership_key = membership_key.ok_or(MlsSpecError::MissingMembershipKey)?;
+            message.membership_tag = Some(message.membership_tag(provider, ctx, membership_key)?);
+        }
+
+        Ok(message)
+    }
+
+    /// Checks that this PublicMessage belongs to the group and epoch of `ctx`, verifies its `membership_tag`
+    /// (carried by member senders only) and its signature, and returns the [AuthenticatedContent] it carries.
+    ///
+    /// The confirmation tag of Commits can only be checked once the new epoch's key schedule has been run,
+    /// with [AuthenticatedContent::verify_confirmation_tag].
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-6.2>
+    pub fn unprotect(
+        self,
+        provider: &impl CipherSuiteProvider,
+        ctx: &GroupContext,
+        signature_public_key: &[u8],
+        membership_key: Option<&[u8]>,
+    ) -> MlsSpecResult<AuthenticatedContent> {
+        if self.content.group_id.as_slice() != ctx.group_id() {
+            return Err(MlsSpecError::MessageGroupIdMismatch);
+        }
+        if self.content.epoch != ctx.epoch {
+            return Err(MlsSpecError::MessageEpochMismatch {
+                expected: ctx.epoch,
+                actual: self.content.epoch,
+            });
+        }
+
+        if matches!(self.content.sender, Sender::Member(_)) {
+            let membership_key = membership_key.ok_or(MlsSpecError::MissingMembershipKey)?;
+            self.verify_membership_tag(provider, ctx, membership_key)?;
+        } else if self.membership_tag.is_some() {
+            return Err(MlsSpecError::UnexpectedMembershipTag);
+        }
+
+        let authenticated_content = self.into_authenticated_content();
+        authenticated_content.verify_signature(provider, signature_public_key, ctx)?;
+        Ok(authenticated_content)
+    }
+
+    /// Verifies, in constant time, the `membership_tag` of this PublicMessage
+    pub fn verify_membership_tag(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        ctx: &GroupContext,
+        membership_key: &[u8],
+    ) -> MlsSpecResult<()> {
+        let membership_tag = self
+            .membership_tag
+            .as_ref()
+            .ok_or(MlsSpecError::MissingMembershipTag)?;
+        let expected = self.membership_tag(provider, ctx, membership_key)?;
+        if !membership_tag.ct_eq_slice(expected.as_slice()) {
+            return Err(MlsSpecError::InvalidMembershipTag);
+        }
+
+        Ok(())
+    }
+
+    /// `membership_tag = MAC(membership_key, AuthenticatedContentTBM)`
+    fn membership_tag(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        ctx: &GroupContext,
+        membership_key: &[u8],
+    ) -> MlsSpecResult<Mac> {
+        let tbm = AuthenticatedContentTBM {
+            content_tbs: self.content.to_tbs(&Self::AUTH_CONTENT_REF_WF, ctx)?,
+            auth: &self.auth,
+        }
+        .to_tls_bytes()?;
+        provider.mac(membership_key, &tbm)
+    }
 }
 
 impl tls_codec::Serialize for PublicMessage {
@@ -172,3 +290,182 @@ pub struct PrivateMessage {
     pub encrypted_sender_data: SensitiveBytes,
     pub ciphertext: SensitiveBytes,
 }
+
+#[cfg(all(test, feature = "rustcrypto"))]
+mod tests {
+    use super::*;
+    use crate::{
+        Parsable as _,
+        group::{
+            commits::Commit,
+            proposals::{Proposal, RemoveProposal},
+        },
+        test_utils::fixtures,
+    };
+
+    const MEMBERSHIP_KEY: &[u8] = &[0x42; 32];
+    const CONFIRMATION_KEY: &[u8] = &[0x43; 32];
+
+    /// Signed `content` sent by `sender`, along with its signature public key
+    fn authenticated_content(
+        provider: &impl CipherSuiteProvider,
+        sender: Sender,
+        content: ContentTypeInner,
+        ctx: &GroupContext,
+    ) -> (AuthenticatedContent, SensitiveBytes) {
+        let mut signature_key_pair = provider.signature_key_generate().unwrap();
+        let authenticated_content = AuthenticatedContent::new_signed(
+            provider,
+            PublicMessage::AUTH_CONTENT_REF_WF,
+            FramedContent {
+                group_id: ctx.group_id().to_vec(),
+                epoch: ctx.epoch,
+                sender,
+                authenticated_data: vec![].into(),
+                content,
+            },
+            &signature_key_pair.extract_secret_key(),
+            ctx,
+        )
+        .unwrap();
+        (
+            authenticated_content,
+            signature_key_pair.extract_public_key(),
+        )
+    }
+
+    fn remove() -> ContentTypeInner {
+        ContentTypeInner::Proposal {
+            proposal: Proposal::Remove(RemoveProposal { removed: 1 }),
+        }
+    }
+
+    #[test]
+    fn protect_rejects_application_data() {
+        let provider = fixtures::provider();
+        let ctx = fixtures::group_context();
+        let application = ContentTypeInner::Application {
+            application_data: b"hi".to_vec(),
+        };
+        let (content, _) = authenticated_content(&provider, Sender::Member(0), application, &ctx);
+        assert!(matches!(
+            PublicMessage::protect(&provider, content, &ctx, Some(MEMBERSHIP_KEY)),
+            Err(MlsSpecError::PublicMessageApplicationData)
+        ));
+    }
+
+    #[test]
+    fn unprotect_checks_group_and_epoch() {
+        let provider = fixtures::provider();
+        let ctx = fixtures::group_context();
+        let (content, signature_key) =
+            authenticated_content(&provider, Sender::Member(0), remove(), &ctx);
+        let message =
+            PublicMessage::protect(&provider, content, &ctx, Some(MEMBERSHIP_KEY)).unwrap();
+        let unprotect = |ctx: &GroupContext| {
+            message
+                .clone()
+                .unprotect(&provider, ctx, &signature_key, Some(MEMBERSHIP_KEY))
+        };
+        unprotect(&ctx).unwrap();
+
+        let mut other_group = GroupContext::with_group_id(b"other group".to_vec());
+        other_group.cipher_suite = ctx.cipher_suite;
+        assert!(matches!(
+            unprotect(&other_group),
+            Err(MlsSpecError::MessageGroupIdMismatch)
+        ));
+
+        let mut next_epoch = ctx.clone();
+        next_epoch.epoch += 1;
+        assert!(matches!(
+            unprotect(&next_epoch),
+            Err(MlsSpecError::MessageEpochMismatch {
+                expected: 1,
+                actual: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn unprotect_checks_membership_tag_presence() {
+        let provider = fixtures::provider();
+        let ctx = fixtures::group_context();
+
+        let (content, signature_key) =
+            authenticated_content(&provider, Sender::Member(0), remove(), &ctx);
+        let mut message =
+            PublicMessage::protect(&provider, content, &ctx, Some(MEMBERSHIP_KEY)).unwrap();
+        message.membership_tag = None;
+        assert!(matches!(
+            message.unprotect(&provider, &ctx, &signature_key, Some(MEMBERSHIP_KEY)),
+            Err(MlsSpecError::MissingMembershipTag)
+        ));
+
+        let (content, signature_key) =
+            authenticated_content(&provider, Sender::External(0), remove(), &ctx);
+        let mut message = PublicMessage::protect(&provider, content, &ctx, None).unwrap();
+        assert!(message.membership_tag.is_none());
+        message
+            .clone()
+            .unprotect(&provider, &ctx, &signature_key, None)
+            .unwrap();
+        message.membership_tag = Some(vec![0; 32].into());
+        assert!(matches!(
+            message.unprotect(&provider, &ctx, &signature_key, Some(MEMBERSHIP_KEY)),
+            Err(MlsSpecError::UnexpectedMembershipTag)
+        ));
+    }
+
+    #[test]
+    fn protect_requires_confirmation_tag_on_commits() {
+        let provider = fixtures::provider();
+        let ctx = fixtures::group_context();
+        let commit = ContentTypeInner::Commit {
+            commit: Commit {
+                proposals: vec![],
+                path: None,
+            },
+        };
+        let (mut content, signature_key) =
+            authenticated_content(&provider, Sender::Member(0), commit, &ctx);
+        assert!(matches!(
+            PublicMessage::protect(&provider, content.clone(), &ctx, Some(MEMBERSHIP_KEY)),
+            Err(MlsSpecError::MissingConfirmationTag)
+        ));
+
+        let confirmed_transcript_hash = b"confirmed transcript hash";
+        content
+            .set_confirmation_tag(&provider, CONFIRMATION_KEY, confirmed_transcript_hash)
+            .unwrap();
+        let message =
+            PublicMessage::protect(&provider, content.clone(), &ctx, Some(MEMBERSHIP_KEY)).unwrap();
+        let message = PublicMessage::from_tls_bytes(&message.to_tls_bytes().unwrap()).unwrap();
+        let unprotected = message
+            .unprotect(&provider, &ctx, &signature_key, Some(MEMBERSHIP_KEY))
+            .unwrap();
+        assert_eq!(unprotected, content);
+        unprotected
+            .verify_confirmation_tag(&provider, CONFIRMATION_KEY, confirmed_transcript_hash)
+            .unwrap();
+        assert!(matches!(
+            unprotected.verify_confirmation_tag(&provider, CONFIRMATION_KEY, b"other hash"),
+            Err(MlsSpecError::InvalidConfirmationTag)
+        ));
+    }
+
+    #[test]
+    fn unprotect_rejects_invalid_membership_tag() {
+        let provider = fixtures::provider();
+        let ctx = fixtures::group_context();
+        let (content, signature_key) =
+            authenticated_content(&provider, Sender::Member(0), remove(), &ctx);
+        let mut message =
+            PublicMessage::protect(&provider, content, &ctx, Some(MEMBERSHIP_KEY)).unwrap();
+        message.membership_tag = Some(vec![0; 32].into());
+        assert!(matches!(
+            message.unprotect(&provider, &ctx, &signature_key, Some(MEMBERSHIP_KEY)),
+            Err(MlsSpecError::InvalidMembershipTag)
+        ));
+    }
+}
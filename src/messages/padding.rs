@@ -0,0 +1,169 @@
+use crate::crypto::CipherSuiteProvider;
+
+/// Strategy used to pick the amount of zero padding appended to the plaintext of
+/// PrivateMessages (and SemiPrivateMessages), in order to hide the exact length of their content.
+///
+/// The policy applies to the whole AEAD plaintext, i.e. the content along with its authentication data.
+///
+/// <https://www.rfc-editor.org/rfc/rfc9420.html#section-16.4.3>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PaddingPolicy {
+    /// No padding at all
+    #[default]
+    None,
+    /// Pads to the next multiple of the given block size. A block size of 0 disables padding.
+    Block(usize),
+    /// Padmé, which bounds the overhead to ~12% while only leaking `O(log log L)` bits of the length
+    ///
+    /// <https://petsymposium.org/popets/2019/popets-2019-0056.pdf>
+    Padme,
+    /// Pads to the smallest bucket that fits the plaintext. Plaintexts larger than every bucket are
+    /// padded to the next multiple of the largest one.
+    Buckets(Vec<usize>),
+}
+
+impl PaddingPolicy {
+    /// Length of the plaintext once padded according to this policy
+    pub fn padded_len(&self, plaintext_len: usize) -> usize {
+        match self {
+            Self::None | Self::Block(0) => plaintext_len,
+            Self::Block(block_size) => plaintext_len.next_multiple_of(*block_size),
+            Self::Padme => {
+                if plaintext_len < 2 {
+                    return plaintext_len;
+                }
+                let exponent = plaintext_len.ilog2();
+                let last_bits = exponent - (exponent.ilog2() + 1);
+                let mask = (1usize << last_bits) - 1;
+                (plaintext_len + mask) & !mask
+            }
+            Self::Buckets(buckets) => buckets
+                .iter()
+                .copied()
+                .filter(|bucket| *bucket >= plaintext_len)
+                .min()
+                .or_else(|| {
+                    buckets
+                        .iter()
+                        .copied()
+                        .max()
+                        .filter(|largest| *largest > 0)
+                        .map(|largest| plaintext_len.next_multiple_of(largest))
+                })
+                .unwrap_or(plaintext_len),
+        }
+    }
+
+    /// Amount of zero padding to append to a plaintext of `plaintext_len` bytes
+    pub fn padding_len(&self, plaintext_len: usize) -> usize {
+        self.padded_len(plaintext_len) - plaintext_len
+    }
+
+    /// Size of the AEAD ciphertext of a plaintext of `plaintext_len` bytes padded according to this policy
+    pub fn ciphertext_len(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        plaintext_len: usize,
+    ) -> usize {
+        self.padded_len(plaintext_len) + provider.aead_tag_size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn padded_lengths() {
+        assert_eq!(PaddingPolicy::None.padded_len(37), 37);
+        assert_eq!(PaddingPolicy::Block(0).padded_len(37), 37);
+        assert_eq!(PaddingPolicy::Block(32).padded_len(37), 64);
+        assert_eq!(PaddingPolicy::Block(32).padded_len(64), 64);
+
+        for (len, padded) in [(0, 0), (1, 1), (9, 10), (37, 40), (100, 104), (1000, 1024)] {
+            assert_eq!(PaddingPolicy::Padme.padded_len(len), padded);
+        }
+        for len in 1..10_000 {
+            let padded = PaddingPolicy::Padme.padded_len(len);
+            assert!(padded >= len && (padded - len) * 100 <= len * 12);
+        }
+
+        let buckets = PaddingPolicy::Buckets(vec![256, 64, 1024]);
+        assert_eq!(buckets.padded_len(10), 64);
+        assert_eq!(buckets.padded_len(64), 64);
+        assert_eq!(buckets.padded_len(65), 256);
+        assert_eq!(buckets.padded_len(1500), 2048);
+        assert_eq!(PaddingPolicy::Buckets(vec![]).padded_len(10), 10);
+    }
+
+    #[cfg(feature = "rustcrypto")]
+    #[test]
+    fn padded_private_messages_roundtrip() {
+        use crate::{
+            Parsable as _, Serializable as _,
+            defs::WireFormat,
+            key_schedule::{SecretTree, SecretTreeConfig},
+            messages::{
+                AuthenticatedContent, ContentTypeInner, FramedContent, PrivateMessage,
+                PrivateMessageContent, Sender,
+            },
+            test_utils::fixtures,
+        };
+
+        let provider = fixtures::provider();
+        let ctx = fixtures::group_context();
+        let sender_data_secret = [0x11; 32];
+        let secret_tree = || SecretTree::new(vec![0x22; 32].into(), 2, SecretTreeConfig::default());
+        let mut signature_key_pair = provider.signature_key_generate().unwrap();
+        let content = AuthenticatedContent::new_signed(
+            &provider,
+            WireFormat::new_unchecked(WireFormat::MLS_PRIVATE_MESSAGE),
+            FramedContent {
+                group_id: ctx.group_id().to_vec(),
+                epoch: ctx.epoch,
+                sender: Sender::Member(1),
+                authenticated_data: vec![].into(),
+                content: ContentTypeInner::Application {
+                    application_data: vec![0xA5; 100],
+                },
+            },
+            &signature_key_pair.extract_secret_key(),
+            &ctx,
+        )
+        .unwrap();
+
+        for padding in [
+            PaddingPolicy::Block(64),
+            PaddingPolicy::Padme,
+            PaddingPolicy::Buckets(vec![64, 256, 1024]),
+        ] {
+            let message = PrivateMessage::encrypt(
+                &provider,
+                &content,
+                &mut secret_tree(),
+                &sender_data_secret,
+                &padding,
+            )
+            .unwrap();
+            let plaintext = PrivateMessageContent::new(
+                content.content.content.clone(),
+                content.auth.clone(),
+                &PaddingPolicy::None,
+            );
+            let plaintext_len = plaintext.to_tls_bytes().unwrap().len();
+            assert_eq!(
+                message.ciphertext.len(),
+                padding.ciphertext_len(&provider, plaintext_len),
+                "{padding:?}"
+            );
+            assert!(padding.padding_len(plaintext_len) > 0, "{padding:?}");
+
+            let message = PrivateMessage::from_tls_bytes(&message.to_tls_bytes().unwrap()).unwrap();
+            let decrypted = message
+                .decrypt(&provider, &ctx, &mut secret_tree(), &sender_data_secret)
+                .unwrap();
+            assert_eq!(decrypted, content);
+        }
+    }
+}
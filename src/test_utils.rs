@@ -112,3 +112,6 @@ pub(crate) mod testing {
         };
     }
 }
+
+#[cfg(all(test, feature = "rustcrypto"))]
+pub(crate) mod fixtures;
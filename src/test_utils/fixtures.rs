@@ -0,0 +1,88 @@
+//! Signed members and trees shared by the unit tests of the crate
+//!
+//! The fixtures build valid objects with the default ciphersuite of [RustCryptoProvider], so
+//! that a test only has to spell out what it tampers with.
+
+use crate::{
+    MlsSpecResult, SensitiveBytes,
+    credential::Credential,
+    crypto::{CipherSuiteProvider, CryptoProvider as _, rustcrypto::RustCryptoProvider},
+    defs::{Capabilities, CiphersuiteId, CredentialType, LeafIndex, ProtocolVersion},
+    group::KeyPackageLifetime,
+    key_schedule::GroupContext,
+    tree::{
+        RatchetTree,
+        leaf_node::{LeafNode, LeafNodeSource},
+    },
+};
+
+pub(crate) fn provider() -> impl CipherSuiteProvider {
+    RustCryptoProvider
+        .cipher_suite_provider(CiphersuiteId::default())
+        .unwrap()
+}
+
+pub(crate) fn group_context() -> GroupContext {
+    let mut group_context = GroupContext::with_group_id(b"group".to_vec());
+    group_context.cipher_suite = CiphersuiteId::default();
+    group_context
+}
+
+/// KeyPackage LeafNode with a basic credential, along with its signature private key
+pub(crate) fn leaf_node(
+    provider: &impl CipherSuiteProvider,
+    identity: &str,
+) -> MlsSpecResult<(LeafNode, SensitiveBytes)> {
+    let mut signature_key_pair = provider.signature_key_generate()?;
+    let mut leaf_node = LeafNode {
+        encryption_key: provider.kem_generate()?.extract_public_key(),
+        signature_key: signature_key_pair.extract_public_key(),
+        credential: Credential::basic(identity.as_bytes().to_vec()),
+        capabilities: Capabilities {
+            versions: vec![ProtocolVersion::default()],
+            ciphersuites: vec![CiphersuiteId::default()],
+            extensions: vec![],
+            proposals: vec![],
+            credentials: vec![CredentialType::default()],
+        },
+        source: LeafNodeSource::KeyPackage {
+            lifetime: KeyPackageLifetime::default(),
+        },
+        extensions: vec![],
+        signature: vec![].into(),
+    };
+    let signature_private_key = signature_key_pair.extract_secret_key();
+    leaf_node.sign(provider, &signature_private_key, None)?;
+    Ok((leaf_node, signature_private_key))
+}
+
+/// Tree of `identities`, in which the first member committed after everyone was added
+///
+/// The `removed` members are blanked before the Commit.
+pub(crate) fn committed_tree(
+    provider: &impl CipherSuiteProvider,
+    identities: &[&str],
+    removed: &[LeafIndex],
+) -> MlsSpecResult<RatchetTree> {
+    let mut tree = RatchetTree::default();
+    let mut committer = None;
+    for identity in identities {
+        let (leaf_node, signature_private_key) = leaf_node(provider, identity)?;
+        committer.get_or_insert((leaf_node.clone(), signature_private_key));
+        tree.add_leaf(leaf_node);
+    }
+    for leaf in removed {
+        tree.remove_leaf(*leaf)?;
+    }
+
+    let (leaf_node, signature_private_key) = committer.expect("at least one member");
+    tree.generate_update_path(
+        provider,
+        0,
+        leaf_node,
+        &signature_private_key,
+        &mut group_context(),
+        &[],
+    )?;
+    Ok(tree)
+}
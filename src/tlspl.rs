@@ -35,14 +35,11 @@ pub fn write_vlvec_prefix<W: std::io::Write>(
         8 => 0xC0,
         _ => unreachable!(),
     };
-    let mut data_len_mix = data_len;
-
-    for i in 0..overhead {
-        let base = if i == 0 { prefix } else { 0x00 };
-        let byte = base | (data_len_mix & 0xFF) as u8;
-        writer.write_all(&[byte])?;
-        data_len_mix >>= 8;
-    }
+    // Variable-length integers are big-endian, with the length of the encoding in the 2 MSBs
+    let mut bytes = (data_len as u64).to_be_bytes();
+    let bytes = &mut bytes[8 - overhead..];
+    bytes[0] |= prefix;
+    writer.write_all(bytes)?;
 
     Ok(overhead)
 }
@@ -112,3 +109,41 @@ pub mod string {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tls_codec::Serialize as _;
+
+    #[test]
+    fn vlvec_prefix_is_big_endian() {
+        // RFC 9000 Section 16 / RFC 9420 Section 2.1.2 examples
+        for (len, prefix) in [
+            (37, &[0x25][..]),
+            (64, &[0x40, 0x40]),
+            (300, &[0x41, 0x2C]),
+            (15_293, &[0x7B, 0xBD]),
+            (494_878_333, &[0x9D, 0x7F, 0x3E, 0x7D]),
+        ] {
+            let mut encoded = vec![];
+            let written = super::write_vlvec_prefix(len, &mut encoded).unwrap();
+            assert_eq!(written, prefix.len());
+            assert_eq!(encoded, prefix);
+            assert_eq!(super::tls_serialized_len_as_vlvec(len), prefix.len() + len);
+        }
+
+        // Round-trips with the encoding of tls_codec
+        let data = vec![0xA5; 300];
+        let mut encoded = vec![];
+        super::write_vlvec_prefix(data.len(), &mut encoded).unwrap();
+        encoded.extend_from_slice(&data);
+        assert_eq!(
+            encoded,
+            tls_codec::VLByteSlice(&data)
+                .tls_serialize_detached()
+                .unwrap()
+        );
+        let decoded: tls_codec::VLBytes =
+            tls_codec::Deserialize::tls_deserialize_exact(&encoded).unwrap();
+        assert_eq!(decoded.as_slice(), data.as_slice());
+    }
+}
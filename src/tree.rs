@@ -1,44 +1,175 @@
+mod diff;
 pub mod hashes;
 pub mod leaf_node;
+pub mod math;
+pub use self::diff::*;
+mod operations;
+mod storage;
+mod treekem;
+pub use self::treekem::*;
+mod validation;
 
 use crate::{
     SensitiveBytes,
     crypto::{HpkeCiphertext, HpkePublicKey},
-    defs::LeafIndex,
-    tree::{hashes::ParentNodeHash, leaf_node::LeafNode},
+    defs::{LeafIndex, NodeIndex},
+    tree::{hashes::ParentNodeHash, leaf_node::LeafNode, storage::NodeStorage},
 };
 
+/// Array representation of the ratchet tree
+///
+/// Nodes are stored in copy-on-write, reference-counted storage: cloning a tree is O(1),
+/// and the clone only copies what it mutates afterwards, so per-epoch snapshots of large
+/// trees are cheap. See [RatchetTree::diff] to persist changes between epochs.
+///
+/// As the nodes aren't contiguous in memory, the tree can't be viewed as a slice anymore.
+/// Indexing, [RatchetTree::get], [RatchetTree::iter] and [RatchetTree::to_vec] behave
+/// like their slice counterparts.
+///
+/// The TLS encoding is the one of `optional<Node> ratchet_tree<V>`
+///
+/// <https://www.rfc-editor.org/rfc/rfc9420.html#section-12.4.3.3>
 #[derive(
-    Debug,
-    Clone,
-    PartialEq,
-    Eq,
-    Hash,
-    Default,
-    tls_codec::TlsDeserialize,
-    tls_codec::TlsSerialize,
-    tls_codec::TlsSize,
+    Debug, Clone, PartialEq, Eq, Hash, Default, tls_codec::TlsSerialize, tls_codec::TlsSize,
 )]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct RatchetTree(Vec<Option<TreeNode>>);
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RatchetTreeNodes", into = "Vec<Option<TreeNode>>")
+)]
+pub struct RatchetTree(NodeStorage);
+
+/// Nodes of a [RatchetTree] as deserialized by serde, before their structure is checked
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(transparent)]
+struct RatchetTreeNodes(Vec<Option<TreeNode>>);
+
+/// Like over TLS, deserializing rejects trees that aren't structurally valid
+#[cfg(feature = "serde")]
+impl TryFrom<RatchetTreeNodes> for RatchetTree {
+    type Error = crate::MlsSpecError;
+
+    fn try_from(value: RatchetTreeNodes) -> Result<Self, Self::Error> {
+        let tree = Self::from(value.0);
+        tree.validate_structure()?;
+        Ok(tree)
+    }
+}
+
+/// Decoding rejects trees that aren't structurally valid, see [RatchetTree::validate_structure].
+///
+/// This includes the empty tree, which has an even number of nodes: a ratchet tree always
+/// holds at least the leaf of its creator.
+impl tls_codec::Deserialize for RatchetTree {
+    fn tls_deserialize<R: std::io::Read>(bytes: &mut R) -> Result<Self, tls_codec::Error>
+    where
+        Self: Sized,
+    {
+        let tree = Self(<_>::tls_deserialize(bytes)?);
+        tree.validate_structure()
+            .map_err(|e| tls_codec::Error::DecodingError(e.to_string()))?;
+        Ok(tree)
+    }
+}
 
 impl RatchetTree {
     pub fn into_inner(self) -> Vec<Option<TreeNode>> {
-        self.0
+        self.0.into_vec()
+    }
+
+    /// Number of nodes in the representation of the tree, including the blank ones
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.len() == 0
+    }
+
+    /// Node at index `x`, or `None` if out of bounds. A blank node is `Some(&None)`
+    pub fn get(&self, x: NodeIndex) -> Option<&Option<TreeNode>> {
+        self.0.slot(x as usize)
+    }
+
+    /// Iterates over the nodes of the tree, `None` being a blank node
+    pub fn iter(&self) -> RatchetTreeIter<'_> {
+        RatchetTreeIter {
+            storage: &self.0,
+            range: 0..self.0.len(),
+        }
+    }
+
+    /// Copies the nodes of the tree into a `Vec`
+    pub fn to_vec(&self) -> Vec<Option<TreeNode>> {
+        self.iter().cloned().collect()
+    }
+}
+
+impl std::ops::Index<usize> for RatchetTree {
+    type Output = Option<TreeNode>;
+
+    fn index(&self, x: usize) -> &Self::Output {
+        self.0.slot(x).unwrap_or_else(|| {
+            panic!(
+                "node index {x} out of range for a tree of {} nodes",
+                self.len()
+            )
+        })
     }
 }
 
+impl<'a> IntoIterator for &'a RatchetTree {
+    type Item = &'a Option<TreeNode>;
+    type IntoIter = RatchetTreeIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the nodes of a [RatchetTree], behaving like the one of a slice
+#[derive(Debug, Clone)]
+pub struct RatchetTreeIter<'a> {
+    storage: &'a NodeStorage,
+    range: std::ops::Range<usize>,
+}
+
+impl<'a> Iterator for RatchetTreeIter<'a> {
+    type Item = &'a Option<TreeNode>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().and_then(|x| self.storage.slot(x))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.range.nth(n).and_then(|x| self.storage.slot(x))
+    }
+}
+
+impl DoubleEndedIterator for RatchetTreeIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range.next_back().and_then(|x| self.storage.slot(x))
+    }
+}
+
+impl ExactSizeIterator for RatchetTreeIter<'_> {}
+
+impl std::iter::FusedIterator for RatchetTreeIter<'_> {}
+
 impl From<Vec<Option<TreeNode>>> for RatchetTree {
     fn from(value: Vec<Option<TreeNode>>) -> Self {
-        Self(value)
+        Self(value.into())
     }
 }
 
-impl std::ops::Deref for RatchetTree {
-    type Target = [Option<TreeNode>];
-
-    fn deref(&self) -> &Self::Target {
-        self.0.as_slice()
+impl From<RatchetTree> for Vec<Option<TreeNode>> {
+    fn from(value: RatchetTree) -> Self {
+        value.into_inner()
     }
 }
 
@@ -183,3 +314,80 @@ pub struct UpdatePath {
     pub leaf_node: LeafNode,
     pub nodes: Vec<UpdatePathNode>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slice_like_access() {
+        let nodes: Vec<Option<TreeNode>> = (0..7u8)
+            .map(|x| {
+                (x % 2 == 1).then(|| {
+                    ParentNode {
+                        encryption_key: vec![x].into(),
+                        parent_hash: vec![].into(),
+                        unmerged_leaves: vec![],
+                    }
+                    .into()
+                })
+            })
+            .collect();
+        let tree = RatchetTree::from(nodes.clone());
+
+        assert_eq!(tree.len(), nodes.len());
+        assert_eq!(tree.to_vec(), nodes);
+        assert!(tree.iter().eq(nodes.iter()));
+        assert!(tree.iter().rev().eq(nodes.iter().rev()));
+        for (x, node) in nodes.iter().enumerate() {
+            assert_eq!(&tree[x], node);
+            assert_eq!(tree.get(x as NodeIndex), Some(node));
+        }
+        assert_eq!(tree.get(nodes.len() as NodeIndex), None);
+    }
+
+    #[test]
+    fn nodes_outside_of_the_tree() {
+        let tree = RatchetTree::from(
+            (0..7u8)
+                .map(|x| {
+                    (x % 2 == 1).then(|| {
+                        ParentNode {
+                            encryption_key: vec![x].into(),
+                            parent_hash: vec![].into(),
+                            unmerged_leaves: vec![],
+                        }
+                        .into()
+                    })
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        assert_eq!(tree.resolution(3), vec![3]);
+        assert_eq!(tree.filtered_direct_path(0), vec![3]);
+        // A blank parent of level 31 would otherwise be resolved down to its 2^31 leaves
+        assert!(tree.resolution((1 << 31) - 1).is_empty());
+        assert!(tree.resolution(9).is_empty());
+        assert!(tree.filtered_direct_path(4).is_empty());
+        assert!(tree.filtered_direct_path(LeafIndex::MAX).is_empty());
+    }
+
+    #[cfg(all(feature = "serde", feature = "rustcrypto"))]
+    #[test]
+    fn serde_rejects_malformed_trees() {
+        let provider = crate::test_utils::fixtures::provider();
+        let tree =
+            crate::test_utils::fixtures::committed_tree(&provider, &["a", "b"], &[]).unwrap();
+        let decode = |nodes: &Vec<Option<TreeNode>>| {
+            postcard::from_bytes::<RatchetTree>(&postcard::to_stdvec(nodes).unwrap())
+        };
+
+        let nodes = tree.to_vec();
+        assert_eq!(decode(&nodes).unwrap(), tree);
+        assert!(decode(&vec![]).is_err());
+        assert!(decode(&nodes[..nodes.len() - 1].to_vec()).is_err());
+        let mut untrimmed = nodes.clone();
+        untrimmed.extend([None, None]);
+        assert!(decode(&untrimmed).is_err());
+    }
+}
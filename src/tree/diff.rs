@@ -0,0 +1,166 @@
+use crate::{
+    MlsSpecError, MlsSpecResult,
+    defs::NodeIndex,
+    tree::{RatchetTree, TreeNode},
+};
+
+/// Changes between two versions of a [RatchetTree], e.g. between two epochs
+///
+/// Storing a full tree once and then a diff per epoch is much more compact than storing
+/// the full tree at every epoch, as a commit only changes a few direct paths.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    tls_codec::TlsSerialize,
+    tls_codec::TlsDeserialize,
+    tls_codec::TlsSize,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RatchetTreeDiff {
+    /// Number of nodes of the newer tree
+    pub node_count: u32,
+    pub changes: Vec<NodeChange>,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    tls_codec::TlsSerialize,
+    tls_codec::TlsDeserialize,
+    tls_codec::TlsSize,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeChange {
+    pub index: NodeIndex,
+    /// New value of the node, `None` if it's been blanked
+    pub node: Option<TreeNode>,
+}
+
+impl RatchetTreeDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl RatchetTree {
+    /// Computes the changes turning this tree into `newer`.
+    ///
+    /// When `newer` is derived from a clone of this tree, only the storage it copied when
+    /// mutating is compared, so the cost is proportional to the changes, not to the tree size.
+    pub fn diff(&self, newer: &Self) -> RatchetTreeDiff {
+        let changes = self
+            .0
+            .changed_nodes(&newer.0)
+            .into_iter()
+            .filter(|x| (*x as usize) < newer.len())
+            .map(|index| NodeChange {
+                index,
+                node: newer.node(index).cloned(),
+            })
+            .collect();
+
+        RatchetTreeDiff {
+            node_count: newer.len() as u32,
+            changes,
+        }
+    }
+
+    /// Applies a diff computed by [RatchetTree::diff].
+    ///
+    /// The resulting tree must be structurally valid, otherwise it's left untouched
+    pub fn apply_diff(&mut self, diff: &RatchetTreeDiff) -> MlsSpecResult<()> {
+        let mut tree = self.clone();
+        tree.0.resize(diff.node_count as usize);
+        for change in &diff.changes {
+            if change.index >= diff.node_count {
+                return Err(MlsSpecError::RatchetTreeDiffOutOfBounds {
+                    index: change.index,
+                    node_count: diff.node_count,
+                });
+            }
+            tree.0.replace(change.index as usize, change.node.clone());
+        }
+
+        tree.validate_structure()?;
+        *self = tree;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "rustcrypto"))]
+mod tests {
+    use super::*;
+    use crate::{Parsable as _, Serializable as _, test_utils::fixtures};
+
+    #[test]
+    fn diff_replays_changes_on_snapshot() {
+        let provider = fixtures::provider();
+        let mut tree = fixtures::committed_tree(&provider, &["a", "b", "c", "d"], &[]).unwrap();
+        let snapshot = tree.clone();
+        let snapshot_bytes = snapshot.to_tls_bytes().unwrap();
+        assert!(snapshot.diff(&tree).is_empty());
+
+        let (leaf_node, _) = fixtures::leaf_node(&provider, "e").unwrap();
+        assert_eq!(tree.add_leaf(leaf_node), 4);
+        let (leaf_node, _) = fixtures::leaf_node(&provider, "c2").unwrap();
+        tree.update_leaf(2, leaf_node).unwrap();
+        tree.remove_leaf(1).unwrap();
+        assert_eq!(snapshot.to_tls_bytes().unwrap(), snapshot_bytes);
+
+        let diff = snapshot.diff(&tree);
+        assert_eq!(diff.node_count as usize, tree.len());
+        let diff = RatchetTreeDiff::from_tls_bytes(&diff.to_tls_bytes().unwrap()).unwrap();
+        let mut applied = snapshot.clone();
+        applied.apply_diff(&diff).unwrap();
+        assert_eq!(applied, tree);
+        assert_eq!(
+            applied.to_tls_bytes().unwrap(),
+            tree.to_tls_bytes().unwrap()
+        );
+
+        // Diffs also shrink trees
+        let mut reverted = tree.clone();
+        reverted.apply_diff(&tree.diff(&snapshot)).unwrap();
+        assert_eq!(reverted.to_tls_bytes().unwrap(), snapshot_bytes);
+    }
+
+    #[test]
+    fn apply_diff_leaves_tree_untouched_on_error() {
+        let provider = fixtures::provider();
+        let tree = fixtures::committed_tree(&provider, &["a", "b"], &[]).unwrap();
+
+        let mut applied = tree.clone();
+        let out_of_bounds = RatchetTreeDiff {
+            node_count: 3,
+            changes: vec![NodeChange {
+                index: 3,
+                node: None,
+            }],
+        };
+        assert!(matches!(
+            applied.apply_diff(&out_of_bounds),
+            Err(MlsSpecError::RatchetTreeDiffOutOfBounds {
+                index: 3,
+                node_count: 3
+            })
+        ));
+        assert_eq!(applied, tree);
+
+        let even = RatchetTreeDiff {
+            node_count: 2,
+            changes: vec![],
+        };
+        assert!(matches!(
+            applied.apply_diff(&even),
+            Err(MlsSpecError::RatchetTreeEvenNodeCount(2))
+        ));
+        assert_eq!(applied, tree);
+    }
+}
@@ -1,7 +1,11 @@
+use std::borrow::Cow;
+
 use crate::{
-    SensitiveBytes,
-    defs::LeafIndex,
-    tree::{NodeType, ParentNode, leaf_node::LeafNode},
+    MlsSpecError, MlsSpecResult, SensitiveBytes, Serializable as _,
+    crypto::CipherSuiteProvider,
+    defs::{LeafIndex, NodeIndex},
+    key_schedule::GroupContext,
+    tree::{NodeType, ParentNode, RatchetTree, TreeHash, leaf_node::LeafNode, math},
 };
 
 pub type ParentNodeHash = SensitiveBytes;
@@ -40,3 +44,377 @@ pub struct ParentHashInput<'a> {
     #[tls_codec(with = "crate::tlspl::bytes")]
     pub original_sibling_tree_hash: &'a [u8],
 }
+
+impl RatchetTree {
+    /// Tree hash of the subtree rooted at node `x`
+    ///
+    /// Tree hashes are cached per node, and changing a node only drops the cached hashes of
+    /// its direct path, so only those get recomputed afterwards.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-7.8>
+    pub fn tree_hash(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        x: NodeIndex,
+    ) -> MlsSpecResult<TreeHash> {
+        self.tree_hash_without(provider, x, &[])
+    }
+
+    /// Tree hash of the subtree rooted at node `x`, computed as if the `removed` leaves were blank
+    /// and absent from every `unmerged_leaves` list
+    fn tree_hash_without(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        x: NodeIndex,
+        removed: &[LeafIndex],
+    ) -> MlsSpecResult<TreeHash> {
+        let cacheable = !removed.iter().any(|leaf| subtree_contains(x, *leaf));
+        if cacheable {
+            if let Some(tree_hash) = self.0.cached_tree_hash(x, provider.ciphersuite()) {
+                return Ok(tree_hash.clone());
+            }
+        }
+
+        let input = if let Some(leaf_index) = math::node_to_leaf(x) {
+            TreeHashInput::Leaf(LeafNodeHashInput {
+                leaf_index: &leaf_index,
+                leaf_node: self
+                    .leaf_node(leaf_index)
+                    .filter(|_| !removed.contains(&leaf_index)),
+            })
+            .to_tls_bytes()?
+        } else {
+            let (Some(left), Some(right)) = (math::left(x), math::right(x)) else {
+                unreachable!("parent nodes always have children");
+            };
+            let parent_node = self.parent_node(x).map(|parent_node| {
+                if parent_node
+                    .unmerged_leaves
+                    .iter()
+                    .any(|leaf| removed.contains(leaf))
+                {
+                    let mut parent_node = parent_node.clone();
+                    parent_node
+                        .unmerged_leaves
+                        .retain(|leaf| !removed.contains(leaf));
+                    Cow::Owned(parent_node)
+                } else {
+                    Cow::Borrowed(parent_node)
+                }
+            });
+            TreeHashInput::Parent(ParentNodeHashInput {
+                parent_node: parent_node.as_deref(),
+                left_hash: self.tree_hash_without(provider, left, removed)?.as_slice(),
+                right_hash: self.tree_hash_without(provider, right, removed)?.as_slice(),
+            })
+            .to_tls_bytes()?
+        };
+
+        let tree_hash = provider.hash(&input)?;
+        if cacheable {
+            self.0
+                .cache_tree_hash(x, provider.ciphersuite(), tree_hash.clone());
+        }
+        Ok(tree_hash)
+    }
+
+    /// Tree hash of the whole tree, which is the one carried by the `GroupContext`
+    pub fn root_tree_hash(&self, provider: &impl CipherSuiteProvider) -> MlsSpecResult<TreeHash> {
+        self.tree_hash(provider, math::root(self.leaf_count()))
+    }
+
+    /// Checks, in constant time, that the tree hash of this tree matches the one of `group_context`
+    pub fn verify_tree_hash(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        group_context: &GroupContext,
+    ) -> MlsSpecResult<()> {
+        let tree_hash = self.root_tree_hash(provider)?;
+        if !group_context.tree_hash.ct_eq_slice(tree_hash.as_slice()) {
+            return Err(MlsSpecError::TreeHashMismatch);
+        }
+
+        Ok(())
+    }
+
+    /// Parent hash of the non-blank `parent` node, as stored in its child on the side opposite to `sibling`
+    ///
+    /// The `original_sibling_tree_hash` is computed without the `unmerged_leaves` of `parent`.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-7.9>
+    pub fn parent_hash(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        parent: NodeIndex,
+        sibling: NodeIndex,
+    ) -> MlsSpecResult<ParentNodeHash> {
+        let parent_node = self
+            .parent_node(parent)
+            .ok_or(MlsSpecError::InvalidParentHash(parent))?;
+        let original_sibling_tree_hash =
+            self.tree_hash_without(provider, sibling, &parent_node.unmerged_leaves)?;
+
+        let input = ParentHashInput {
+            encryption_key: parent_node.encryption_key.as_slice(),
+            parent_hash: parent_node.parent_hash.as_slice(),
+            original_sibling_tree_hash: original_sibling_tree_hash.as_slice(),
+        };
+        provider.hash(&input.to_tls_bytes()?)
+    }
+
+    /// Verifies that every non-blank parent node is parent-hash valid, i.e. that it belongs to
+    /// a parent hash chain starting from the `parent_hash` of a leaf whose source is a Commit
+    ///
+    /// With the `rayon` feature, the chains of the leaves are followed in parallel.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-7.9.2>
+    pub fn verify_parent_hashes(&self, provider: &impl CipherSuiteProvider) -> MlsSpecResult<()> {
+        let mut unverified = (0..self.len() as NodeIndex)
+            .filter(|x| self.parent_node(*x).is_some())
+            .collect::<std::collections::BTreeSet<_>>();
+
+        let chain = |(leaf, leaf_node)| self.parent_hash_chain(provider, leaf, leaf_node);
+        #[cfg(feature = "rayon")]
+        let chains = {
+            use rayon::prelude::*;
+            self.leaves()
+                .collect::<Vec<_>>()
+                .into_par_iter()
+                .map(chain)
+                .collect::<Vec<_>>()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let chains = self.leaves().map(chain);
+
+        // Errors are reported in leaf order, so that both modes behave identically
+        for verified in chains {
+            for parent in verified? {
+                unverified.remove(&parent);
+            }
+        }
+
+        if let Some(parent) = unverified.first() {
+            return Err(MlsSpecError::InvalidParentHash(*parent));
+        }
+
+        Ok(())
+    }
+
+    /// Parent nodes verified by following the parent hash chain starting from `leaf`, up to the
+    /// first invalid link
+    ///
+    /// A node D is linked to the ancestor P whose parent hash it holds, provided that D is in the
+    /// resolution of the child C of P on the side of D, and that the unmerged leaves of P under C
+    /// are the rest of that resolution.
+    fn parent_hash_chain(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        leaf: LeafIndex,
+        leaf_node: &LeafNode,
+    ) -> MlsSpecResult<Vec<NodeIndex>> {
+        let mut verified = vec![];
+        let Some(mut parent_hash) = leaf_node.parent_hash() else {
+            return Ok(verified);
+        };
+
+        let leaf_count = self.leaf_count();
+        let x = math::leaf_to_node(leaf);
+        let mut sibling = math::sibling(x, leaf_count);
+        let mut child = x;
+        let mut chain_node = x;
+        for parent in math::direct_path(x, leaf_count) {
+            let copath_child = sibling;
+            let path_child = std::mem::replace(&mut child, parent);
+            sibling = math::sibling(parent, leaf_count);
+            let (Some(parent_node), Some(copath_child)) = (self.parent_node(parent), copath_child)
+            else {
+                continue;
+            };
+
+            let expected = self.parent_hash(provider, parent, copath_child)?;
+            if !expected.ct_eq_slice(parent_hash) {
+                continue;
+            }
+
+            let mut resolution = self.resolution(path_child);
+            let Some(position) = resolution.iter().position(|y| *y == chain_node) else {
+                break;
+            };
+            resolution.swap_remove(position);
+            resolution.sort_unstable();
+            let mut unmerged = parent_node
+                .unmerged_leaves
+                .iter()
+                .filter(|leaf| subtree_contains(path_child, **leaf))
+                .map(|leaf| math::leaf_to_node(*leaf))
+                .collect::<Vec<_>>();
+            unmerged.sort_unstable();
+            if unmerged != resolution {
+                break;
+            }
+
+            verified.push(parent);
+            chain_node = parent;
+            parent_hash = parent_node.parent_hash.as_slice();
+        }
+
+        Ok(verified)
+    }
+}
+
+/// Whether `leaf` belongs to the subtree rooted at node `x`
+fn subtree_contains(x: NodeIndex, leaf: LeafIndex) -> bool {
+    let k = math::level(x) + 1;
+    math::leaf_to_node(leaf).checked_shr(k) == x.checked_shr(k)
+}
+
+#[cfg(all(test, feature = "rustcrypto"))]
+mod tests {
+    use super::*;
+    use crate::{Parsable as _, test_utils::fixtures, tree::TreeNode};
+
+    fn assert_cached_hashes_fresh(tree: &RatchetTree, provider: &impl CipherSuiteProvider) {
+        let fresh = RatchetTree::from_tls_bytes(&tree.to_tls_bytes().unwrap()).unwrap();
+        for x in 0..tree.len() as NodeIndex {
+            assert_eq!(
+                tree.tree_hash(provider, x).unwrap(),
+                fresh.tree_hash(provider, x).unwrap(),
+                "tree hash of node {x}"
+            );
+        }
+    }
+
+    #[test]
+    fn cached_tree_hashes_follow_tree_operations() {
+        let provider = fixtures::provider();
+        let mut tree = fixtures::committed_tree(&provider, &["a", "b", "c", "d"], &[]).unwrap();
+        let root_hash = tree.root_tree_hash(&provider).unwrap();
+        let snapshot = tree.clone();
+
+        let (leaf_node, _) = fixtures::leaf_node(&provider, "e").unwrap();
+        tree.add_leaf(leaf_node);
+        assert_cached_hashes_fresh(&tree, &provider);
+
+        let (leaf_node, _) = fixtures::leaf_node(&provider, "c2").unwrap();
+        tree.update_leaf(2, leaf_node).unwrap();
+        assert_cached_hashes_fresh(&tree, &provider);
+
+        tree.remove_leaf(4).unwrap();
+        assert_cached_hashes_fresh(&tree, &provider);
+        assert_eq!(tree.len(), snapshot.len());
+
+        assert_eq!(snapshot.root_tree_hash(&provider).unwrap(), root_hash);
+    }
+
+    #[test]
+    fn verify_tree_hash_rejects_mismatch() {
+        let provider = fixtures::provider();
+        let tree = fixtures::committed_tree(&provider, &["a", "b"], &[]).unwrap();
+        let mut group_context = fixtures::group_context();
+        group_context.tree_hash = tree.root_tree_hash(&provider).unwrap();
+        tree.verify_tree_hash(&provider, &group_context).unwrap();
+
+        group_context.tree_hash = tree.tree_hash(&provider, 0).unwrap();
+        assert!(matches!(
+            tree.verify_tree_hash(&provider, &group_context),
+            Err(MlsSpecError::TreeHashMismatch)
+        ));
+    }
+
+    #[test]
+    fn verify_parent_hashes_rejects_tampered_parent_node() {
+        let provider = fixtures::provider();
+        let mut tree = fixtures::committed_tree(&provider, &["a", "b", "c", "d"], &[]).unwrap();
+        tree.verify_parent_hashes(&provider).unwrap();
+        tree.node_mut(3)
+            .and_then(TreeNode::as_parent_node_mut)
+            .unwrap()
+            .parent_hash = b"tampered".to_vec().into();
+        assert!(matches!(
+            tree.verify_parent_hashes(&provider),
+            Err(MlsSpecError::InvalidParentHash(3))
+        ));
+    }
+
+    #[test]
+    fn parent_hash_chain_requires_chained_node_in_resolution() {
+        let provider = fixtures::provider();
+        // Leaf 1 is blank, so the Commit of leaf 0 skips node 1 and sets node 3
+        let mut tree = fixtures::committed_tree(&provider, &["a", "b", "c", "d"], &[1]).unwrap();
+        assert!(tree.node(1).is_none());
+        assert_eq!(
+            tree.parent_hash_chain(&provider, 0, tree.leaf_node(0).unwrap())
+                .unwrap(),
+            [3]
+        );
+
+        // Leaf 0 isn't in the resolution of node 1 anymore, while still carrying the parent hash of node 3
+        let stray = tree.parent_node(3).unwrap().clone();
+        tree.set_node(1, TreeNode::ParentNode(stray));
+        let leaf_node = tree.leaf_node(0).unwrap();
+        assert!(
+            tree.parent_hash(&provider, 3, 5)
+                .unwrap()
+                .ct_eq_slice(leaf_node.parent_hash().unwrap())
+        );
+        assert!(!tree.resolution(1).contains(&0));
+        assert!(
+            tree.parent_hash_chain(&provider, 0, leaf_node)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(matches!(
+            tree.verify_parent_hashes(&provider),
+            Err(MlsSpecError::InvalidParentHash(_))
+        ));
+    }
+
+    #[test]
+    fn parent_hash_chain_requires_matching_unmerged_leaves() {
+        let provider = fixtures::provider();
+        let mut tree = fixtures::committed_tree(&provider, &["a", "b", "c", "d"], &[]).unwrap();
+        assert_eq!(
+            tree.parent_hash_chain(&provider, 0, tree.leaf_node(0).unwrap())
+                .unwrap(),
+            [1, 3]
+        );
+        tree.verify_parent_hashes(&provider).unwrap();
+
+        // Leaf 1 is under node 1 but isn't in its resolution, and isn't hashed along with node 3
+        let Some(TreeNode::ParentNode(parent_node)) = tree.node_mut(3) else {
+            unreachable!()
+        };
+        parent_node.unmerged_leaves.push(1);
+        assert!(
+            tree.parent_hash(&provider, 3, 5)
+                .unwrap()
+                .ct_eq_slice(tree.parent_node(1).unwrap().parent_hash.as_slice())
+        );
+        assert_eq!(
+            tree.parent_hash_chain(&provider, 0, tree.leaf_node(0).unwrap())
+                .unwrap(),
+            [1]
+        );
+        assert!(matches!(
+            tree.verify_parent_hashes(&provider),
+            Err(MlsSpecError::InvalidParentHash(3))
+        ));
+    }
+
+    #[test]
+    fn parent_hash_errors_are_reported_in_node_order() {
+        let provider = fixtures::provider();
+        let mut tree = fixtures::committed_tree(&provider, &["a", "b", "c", "d"], &[]).unwrap();
+        tree.verify_parent_hashes(&provider).unwrap();
+        for x in [3, 1] {
+            tree.node_mut(x)
+                .and_then(TreeNode::as_parent_node_mut)
+                .unwrap()
+                .parent_hash = b"tampered".to_vec().into();
+        }
+        assert!(matches!(
+            tree.verify_parent_hashes(&provider),
+            Err(MlsSpecError::InvalidParentHash(1))
+        ));
+    }
+}
@@ -1,7 +1,10 @@
 use crate::{
-    SensitiveBytes,
+    MlsSpecError, MlsSpecResult, SensitiveBytes,
     credential::Credential,
-    crypto::{HpkePublicKey, HpkePublicKeyRef, SignaturePublicKey, SignaturePublicKeyRef},
+    crypto::{
+        CipherSuiteProvider, HpkePublicKey, HpkePublicKeyRef, Signable as _, SignaturePublicKey,
+        SignaturePublicKeyRef,
+    },
     defs::{Capabilities, LeafIndex},
     group::{KeyPackageLifetime, extensions::Extension},
 };
@@ -121,6 +124,35 @@ impl LeafNode {
         })
     }
 
+    /// Signs the LeafNode with the private key matching `signature_key`.
+    ///
+    /// `member_info` is required for `Update` and `Commit` LeafNodes
+    pub fn sign(
+        &mut self,
+        provider: &impl CipherSuiteProvider,
+        signature_private_key: &[u8],
+        member_info: Option<LeafNodeMemberInfo>,
+    ) -> MlsSpecResult<()> {
+        self.signature = self
+            .to_tbs(member_info)
+            .ok_or(MlsSpecError::LeafNodeTBSMissingMemberInfo)?
+            .sign(provider, signature_private_key)?;
+        Ok(())
+    }
+
+    /// Verifies the LeafNode signature using its own `signature_key`.
+    ///
+    /// `member_info` is required for `Update` and `Commit` LeafNodes
+    pub fn verify_signature(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        member_info: Option<LeafNodeMemberInfo>,
+    ) -> MlsSpecResult<()> {
+        self.to_tbs(member_info)
+            .ok_or(MlsSpecError::LeafNodeTBSMissingMemberInfo)?
+            .verify(provider, &self.signature_key, &self.signature)
+    }
+
     pub fn application_id(&self) -> Option<&[u8]> {
         self.extensions.iter().find_map(|ext| {
             if let Extension::ApplicationId(app_id) = ext {
@@ -0,0 +1,130 @@
+//! Array-based tree math over node indices, where leaves sit at even indices and parents at odd ones.
+//!
+//! Functions taking a leaf count `n` expect it to describe a full tree, i.e. to be a power of two.
+//!
+//! <https://www.rfc-editor.org/rfc/rfc9420.html#appendix-C>
+
+use crate::defs::{LeafIndex, NodeIndex};
+
+/// Level of a node in the tree. Leaves are at level 0, their parents at level 1, etc.
+pub fn level(x: NodeIndex) -> u32 {
+    x.trailing_ones()
+}
+
+pub fn is_leaf(x: NodeIndex) -> bool {
+    x & 0x01 == 0
+}
+
+/// Number of nodes needed to represent a tree with `n` leaves
+pub fn node_width(n: LeafIndex) -> u32 {
+    if n == 0 { 0 } else { 2 * (n - 1) + 1 }
+}
+
+/// Index of the root node of a tree with `n` leaves
+pub fn root(n: LeafIndex) -> NodeIndex {
+    let width = node_width(n);
+    if width == 0 {
+        return 0;
+    }
+    (1 << width.ilog2()) - 1
+}
+
+pub fn leaf_to_node(leaf: LeafIndex) -> NodeIndex {
+    leaf * 2
+}
+
+/// Returns `None` if `x` is a parent node
+pub fn node_to_leaf(x: NodeIndex) -> Option<LeafIndex> {
+    is_leaf(x).then_some(x / 2)
+}
+
+/// Left child of an intermediate node. Returns `None` for leaves.
+pub fn left(x: NodeIndex) -> Option<NodeIndex> {
+    let k = level(x);
+    (k > 0).then(|| x ^ (0x01 << (k - 1)))
+}
+
+/// Right child of an intermediate node. Returns `None` for leaves.
+pub fn right(x: NodeIndex) -> Option<NodeIndex> {
+    let k = level(x);
+    (k > 0).then(|| x ^ (0x03 << (k - 1)))
+}
+
+/// Parent of a node in an infinite tree
+pub(crate) fn parent_step(x: NodeIndex) -> NodeIndex {
+    let k = level(x);
+    let b = (x >> (k + 1)) & 0x01;
+    (x | (1 << k)) ^ (b << (k + 1))
+}
+
+/// Immediate parent of a node in a tree with `n` leaves. Returns `None` for the root
+/// and for nodes outside of the tree.
+pub fn parent(x: NodeIndex, n: LeafIndex) -> Option<NodeIndex> {
+    if x == root(n) || x >= node_width(n) {
+        return None;
+    }
+
+    let mut p = parent_step(x);
+    while p >= node_width(n) {
+        p = parent_step(p);
+    }
+    Some(p)
+}
+
+/// Other child of the parent of a node in a tree with `n` leaves. Returns `None` for the root
+/// and for nodes outside of the tree.
+pub fn sibling(x: NodeIndex, n: LeafIndex) -> Option<NodeIndex> {
+    let p = parent(x, n)?;
+    if x < p { right(p) } else { left(p) }
+}
+
+/// Ancestors of a node, from its parent up to and including the root. Empty for nodes outside of the tree.
+pub fn direct_path(x: NodeIndex, n: LeafIndex) -> Vec<NodeIndex> {
+    std::iter::successors(parent(x, n), |p| parent(*p, n)).collect()
+}
+
+/// Siblings of the node and of its ancestors below the root, i.e. the nodes whose
+/// subtrees are combined with the ones on the direct path
+pub fn copath(x: NodeIndex, n: LeafIndex) -> Vec<NodeIndex> {
+    std::iter::once(x)
+        .chain(direct_path(x, n))
+        .filter_map(|y| sibling(y, n))
+        .collect()
+}
+
+/// Lowest common ancestor of two nodes
+pub fn common_ancestor(x: NodeIndex, y: NodeIndex) -> NodeIndex {
+    let (lx, ly) = (level(x) + 1, level(y) + 1);
+    if lx <= ly && x >> ly == y >> ly {
+        return y;
+    } else if ly <= lx && x >> lx == y >> lx {
+        return x;
+    }
+
+    let (mut xn, mut yn) = (x, y);
+    let mut k = 0;
+    while xn != yn {
+        xn >>= 1;
+        yn >>= 1;
+        k += 1;
+    }
+    (xn << k) + (1 << (k - 1)) - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nodes_outside_of_the_tree_have_no_parent() {
+        assert_eq!(parent(5, 4), Some(3));
+        assert_eq!(parent(3, 4), None);
+        assert_eq!(parent(7, 4), None);
+        assert_eq!(parent(NodeIndex::MAX - 1, 4), None);
+        assert_eq!(parent(0, 0), None);
+
+        assert!(direct_path(8, 4).is_empty());
+        assert!(copath(NodeIndex::MAX - 1, 4).is_empty());
+        assert_eq!(sibling(7, 4), None);
+    }
+}
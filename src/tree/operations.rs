@@ -0,0 +1,177 @@
+use crate::{
+    MlsSpecError, MlsSpecResult,
+    defs::{LeafIndex, NodeIndex},
+    tree::{ParentNode, RatchetTree, TreeNode, leaf_node::LeafNode, math},
+};
+
+impl RatchetTree {
+    /// Number of leaves of the full tree, including the trailing blank leaves omitted from the representation
+    pub fn leaf_count(&self) -> LeafIndex {
+        match self.0.len().div_ceil(2) as LeafIndex {
+            0 => 0,
+            leaves => leaves.next_power_of_two(),
+        }
+    }
+
+    /// Returns the node at index `x`, or `None` if it's blank
+    pub fn node(&self, x: NodeIndex) -> Option<&TreeNode> {
+        self.0.get(x as usize)
+    }
+
+    /// Returns the LeafNode of `leaf`, or `None` if it's blank
+    pub fn leaf_node(&self, leaf: LeafIndex) -> Option<&LeafNode> {
+        self.node(math::leaf_to_node(leaf))?.as_leaf_node()
+    }
+
+    /// Returns the ParentNode at index `x`, or `None` if it's blank
+    pub fn parent_node(&self, x: NodeIndex) -> Option<&ParentNode> {
+        self.node(x)?.as_parent_node()
+    }
+
+    /// Iterates over the non-blank leaves of the tree
+    pub fn leaves(&self) -> impl Iterator<Item = (LeafIndex, &LeafNode)> {
+        self.0
+            .iter()
+            .step_by(2)
+            .enumerate()
+            .filter_map(|(leaf, node)| Some((leaf as LeafIndex, node?.as_leaf_node()?)))
+    }
+
+    /// Resolution of node `x`, i.e. the minimal set of non-blank nodes covering all the
+    /// non-blank leaves of its subtree, followed by its unmerged leaves. Empty for nodes outside of the tree.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-4.1.1>
+    pub fn resolution(&self, x: NodeIndex) -> Vec<NodeIndex> {
+        if x >= math::node_width(self.leaf_count()) {
+            return vec![];
+        }
+
+        match self.node(x) {
+            Some(TreeNode::LeafNode(_)) => vec![x],
+            Some(TreeNode::ParentNode(parent_node)) => std::iter::once(x)
+                .chain(
+                    parent_node
+                        .unmerged_leaves
+                        .iter()
+                        .map(|leaf| math::leaf_to_node(*leaf)),
+                )
+                .collect(),
+            None => match (math::left(x), math::right(x)) {
+                (Some(left), Some(right)) => {
+                    let mut resolution = self.resolution(left);
+                    resolution.extend(self.resolution(right));
+                    resolution
+                }
+                _ => vec![],
+            },
+        }
+    }
+
+    /// Direct path of `leaf`, without the nodes whose child on the copath has an empty resolution.
+    /// Empty for leaves outside of the tree.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-4.1.2>
+    pub fn filtered_direct_path(&self, leaf: LeafIndex) -> Vec<NodeIndex> {
+        self.filtered_direct_path_with_copath(leaf)
+            .into_iter()
+            .map(|(parent, _)| parent)
+            .collect()
+    }
+
+    /// Filtered direct path of `leaf`, along with the child of each node that is on the copath
+    pub(crate) fn filtered_direct_path_with_copath(
+        &self,
+        leaf: LeafIndex,
+    ) -> Vec<(NodeIndex, NodeIndex)> {
+        let leaf_count = self.leaf_count();
+        if leaf >= leaf_count {
+            return vec![];
+        }
+
+        let x = math::leaf_to_node(leaf);
+        math::direct_path(x, leaf_count)
+            .into_iter()
+            .zip(math::copath(x, leaf_count))
+            .filter(|(_, copath_child)| !self.resolution(*copath_child).is_empty())
+            .collect()
+    }
+
+    /// Inserts `leaf_node` into the leftmost blank leaf, extending the tree when there's none,
+    /// and marks it as unmerged on the non-blank parent nodes of its direct path.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-7.7>
+    pub fn add_leaf(&mut self, leaf_node: LeafNode) -> LeafIndex {
+        let leaf_count = self.leaf_count();
+        let leaf = (0..leaf_count)
+            .find(|leaf| self.node(math::leaf_to_node(*leaf)).is_none())
+            .unwrap_or(leaf_count);
+
+        let x = math::leaf_to_node(leaf);
+        self.set_node(x, leaf_node.into());
+
+        for p in math::direct_path(x, self.leaf_count()) {
+            if let Some(TreeNode::ParentNode(parent)) = self.node_mut(p) {
+                parent.unmerged_leaves.push(leaf);
+            }
+        }
+
+        leaf
+    }
+
+    /// Replaces the LeafNode of `leaf` and blanks its direct path, as done when applying an Update
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-12.1.2>
+    pub fn update_leaf(&mut self, leaf: LeafIndex, leaf_node: LeafNode) -> MlsSpecResult<()> {
+        let node = self
+            .node_mut(math::leaf_to_node(leaf))
+            .ok_or(MlsSpecError::RatchetTreeBlankLeaf(leaf))?;
+        *node = leaf_node.into();
+        self.blank_direct_path(leaf);
+        Ok(())
+    }
+
+    /// Blanks `leaf` and its direct path, then truncates the tree, as done when applying a Remove.
+    ///
+    /// Returns the LeafNode of the removed member.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-12.1.3>
+    pub fn remove_leaf(&mut self, leaf: LeafIndex) -> MlsSpecResult<LeafNode> {
+        let Some(TreeNode::LeafNode(leaf_node)) =
+            self.0.replace(math::leaf_to_node(leaf) as usize, None)
+        else {
+            return Err(MlsSpecError::RatchetTreeBlankLeaf(leaf));
+        };
+        self.blank_direct_path(leaf);
+        self.truncate();
+        Ok(leaf_node)
+    }
+
+    /// Blanks the intermediate nodes on the direct path of `leaf`
+    pub fn blank_direct_path(&mut self, leaf: LeafIndex) {
+        for p in math::direct_path(math::leaf_to_node(leaf), self.leaf_count()) {
+            self.0.replace(p as usize, None);
+        }
+    }
+
+    /// Removes the right subtrees that only contain blank leaves, so that the representation
+    /// of the tree ends with its rightmost non-blank leaf
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-12.1.3-3.3>
+    pub fn truncate(&mut self) {
+        let width = self
+            .leaves()
+            .last()
+            .map(|(leaf, _)| math::leaf_to_node(leaf) as usize + 1)
+            .unwrap_or_default();
+        self.0.resize(width);
+    }
+
+    /// Sets the node at index `x`, extending the representation of the tree if needed
+    pub(crate) fn set_node(&mut self, x: NodeIndex, node: TreeNode) {
+        self.0.replace(x as usize, Some(node));
+    }
+
+    pub(crate) fn node_mut(&mut self, x: NodeIndex) -> Option<&mut TreeNode> {
+        self.0.get_mut(x as usize)
+    }
+}
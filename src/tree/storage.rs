@@ -0,0 +1,403 @@
+use std::sync::{Arc, OnceLock};
+
+use crate::{
+    defs::{CiphersuiteId, LeafIndex, NodeIndex},
+    tree::{TreeHash, TreeNode, math},
+};
+
+/// Number of items held by each storage chunk
+const CHUNK_LEN: usize = 256;
+
+/// Vector split in reference-counted chunks, so that cloning it is O(1) and mutating
+/// an item after a clone only copies the chunk holding it
+#[derive(Clone)]
+struct CowVec<T> {
+    chunks: Arc<Vec<Arc<Vec<T>>>>,
+    len: usize,
+}
+
+impl<T> Default for CowVec<T> {
+    fn default() -> Self {
+        Self {
+            chunks: Default::default(),
+            len: 0,
+        }
+    }
+}
+
+impl<T: Clone + Default> CowVec<T> {
+    fn get(&self, i: usize) -> Option<&T> {
+        self.chunks.get(i / CHUNK_LEN)?.get(i % CHUNK_LEN)
+    }
+
+    fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        if i >= self.len {
+            return None;
+        }
+        let chunk = Arc::make_mut(&mut Arc::make_mut(&mut self.chunks)[i / CHUNK_LEN]);
+        chunk.get_mut(i % CHUNK_LEN)
+    }
+
+    /// Shortens or extends the vector to `len` items, padding with default ones
+    fn resize(&mut self, len: usize) {
+        if len == self.len {
+            return;
+        }
+
+        let chunks = Arc::make_mut(&mut self.chunks);
+        chunks.resize_with(len.div_ceil(CHUNK_LEN), Default::default);
+        // Only the previous and the new last chunks can have the wrong length
+        for (c, chunk) in chunks
+            .iter_mut()
+            .enumerate()
+            .skip(self.len.min(len) / CHUNK_LEN)
+        {
+            let chunk_len = (len - c * CHUNK_LEN).min(CHUNK_LEN);
+            if chunk.len() != chunk_len {
+                Arc::make_mut(chunk).resize(chunk_len, T::default());
+            }
+        }
+
+        self.len = len;
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &T> {
+        self.chunks.iter().flat_map(|chunk| chunk.iter())
+    }
+
+    /// Indices of the items that differ between both vectors, according to `same`.
+    ///
+    /// Chunks that are still shared are skipped without being compared
+    fn changed(&self, other: &Self, same: impl Fn(&T, &T) -> bool) -> Vec<usize> {
+        let mut changed = vec![];
+        if Arc::ptr_eq(&self.chunks, &other.chunks) {
+            return changed;
+        }
+
+        let default = T::default();
+        for c in 0..self.chunks.len().max(other.chunks.len()) {
+            let (a, b) = (self.chunks.get(c), other.chunks.get(c));
+            if matches!((a, b), (Some(a), Some(b)) if Arc::ptr_eq(a, b)) {
+                continue;
+            }
+
+            for i in 0..CHUNK_LEN {
+                let a = a.and_then(|chunk| chunk.get(i)).unwrap_or(&default);
+                let b = b.and_then(|chunk| chunk.get(i)).unwrap_or(&default);
+                if !same(a, b) {
+                    changed.push(c * CHUNK_LEN + i);
+                }
+            }
+        }
+
+        changed
+    }
+
+    fn into_items(self) -> impl Iterator<Item = T> {
+        Arc::unwrap_or_clone(self.chunks)
+            .into_iter()
+            .flat_map(Arc::unwrap_or_clone)
+    }
+}
+
+impl<T> FromIterator<T> for CowVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut items = iter.into_iter();
+        let mut chunks = vec![];
+        let mut len = 0;
+        loop {
+            let chunk: Vec<T> = items.by_ref().take(CHUNK_LEN).collect();
+            if chunk.is_empty() {
+                break;
+            }
+            len += chunk.len();
+            chunks.push(Arc::new(chunk));
+        }
+
+        Self {
+            chunks: Arc::new(chunks),
+            len,
+        }
+    }
+}
+
+/// Tree hash of a node, along with the ciphersuite it's been computed with
+type CachedTreeHash = OnceLock<(CiphersuiteId, TreeHash)>;
+
+/// Copy-on-write storage of the nodes of a [RatchetTree](crate::tree::RatchetTree)
+///
+/// Nodes are reference-counted and grouped in reference-counted chunks, so that cloning
+/// the storage is O(1) and mutating a node after a clone only copies the chunk holding it
+/// along with the node itself. Everything else stays shared between both copies.
+///
+/// The tree hashes of the nodes are cached alongside them, for every node of the full tree.
+/// Changing a node drops the cached hashes of the node and its ancestors only.
+#[derive(Clone, Default)]
+pub(crate) struct NodeStorage {
+    nodes: CowVec<Arc<Option<TreeNode>>>,
+    tree_hashes: CowVec<CachedTreeHash>,
+}
+
+impl NodeStorage {
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.nodes.len
+    }
+
+    pub(crate) fn get(&self, x: usize) -> Option<&TreeNode> {
+        self.slot(x)?.as_ref()
+    }
+
+    /// Node at index `x`, `Some(None)` being a blank node within the storage
+    pub(crate) fn slot(&self, x: usize) -> Option<&Option<TreeNode>> {
+        self.nodes.get(x).map(|node| &**node)
+    }
+
+    /// Mutable access to a non-blank node, copying it first if it's shared with another tree
+    pub(crate) fn get_mut(&mut self, x: usize) -> Option<&mut TreeNode> {
+        self.get(x)?;
+        self.invalidate_tree_hashes(x);
+        Arc::make_mut(self.nodes.get_mut(x)?).as_mut()
+    }
+
+    /// Replaces the node at index `x`, extending the storage with blank nodes if needed.
+    ///
+    /// Returns the previous node
+    pub(crate) fn replace(&mut self, x: usize, node: Option<TreeNode>) -> Option<TreeNode> {
+        if x >= self.len() {
+            // Blanking a node outside of the storage is a no-op
+            node.as_ref()?;
+            self.resize(x + 1);
+        }
+        self.invalidate_tree_hashes(x);
+        Arc::unwrap_or_clone(std::mem::replace(self.nodes.get_mut(x)?, Arc::new(node)))
+    }
+
+    /// Shortens or extends the storage to `len` nodes, padding with blank nodes
+    pub(crate) fn resize(&mut self, len: usize) {
+        for x in len..self.len() {
+            if self.get(x).is_some() {
+                self.replace(x, None);
+            }
+        }
+        self.nodes.resize(len);
+        self.tree_hashes.resize(Self::full_width(len));
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = Option<&TreeNode>> {
+        self.nodes.iter().map(|node| node.as_ref().as_ref())
+    }
+
+    /// Indices of the nodes that differ between both storages.
+    ///
+    /// Chunks and nodes that are still shared are skipped without being compared
+    pub(crate) fn changed_nodes(&self, other: &Self) -> Vec<NodeIndex> {
+        self.nodes
+            .changed(&other.nodes, |a, b| Arc::ptr_eq(a, b) || a == b)
+            .into_iter()
+            .map(|x| x as NodeIndex)
+            .collect()
+    }
+
+    pub(crate) fn into_vec(self) -> Vec<Option<TreeNode>> {
+        self.nodes.into_items().map(Arc::unwrap_or_clone).collect()
+    }
+
+    /// Cached tree hash of node `x`, if it's been computed with `ciphersuite`
+    pub(crate) fn cached_tree_hash(
+        &self,
+        x: NodeIndex,
+        ciphersuite: CiphersuiteId,
+    ) -> Option<&TreeHash> {
+        let (cached_ciphersuite, tree_hash) = self.tree_hashes.get(x as usize)?.get()?;
+        (*cached_ciphersuite == ciphersuite).then_some(tree_hash)
+    }
+
+    pub(crate) fn cache_tree_hash(
+        &self,
+        x: NodeIndex,
+        ciphersuite: CiphersuiteId,
+        tree_hash: TreeHash,
+    ) {
+        if let Some(cached) = self.tree_hashes.get(x as usize) {
+            // Another thread may have cached the same hash in the meantime
+            let _ = cached.set((ciphersuite, tree_hash));
+        }
+    }
+
+    /// Drops the cached tree hashes of node `x` and all its ancestors.
+    ///
+    /// Their chunks get copied even if already empty, as hashes are cached through shared
+    /// references and must not end up in the chunks of other trees sharing them.
+    fn invalidate_tree_hashes(&mut self, x: usize) {
+        let mut x = x as NodeIndex;
+        while let Some(cached) = self.tree_hashes.get_mut(x as usize) {
+            cached.take();
+            x = math::parent_step(x);
+        }
+    }
+
+    /// Number of nodes of the full tree represented by `len` nodes
+    fn full_width(len: usize) -> usize {
+        match len.div_ceil(2) as LeafIndex {
+            0 => 0,
+            leaf_count => math::node_width(leaf_count.next_power_of_two()) as usize,
+        }
+    }
+}
+
+impl From<Vec<Option<TreeNode>>> for NodeStorage {
+    fn from(value: Vec<Option<TreeNode>>) -> Self {
+        let nodes: CowVec<_> = value.into_iter().map(Arc::new).collect();
+        let mut tree_hashes = CowVec::default();
+        tree_hashes.resize(Self::full_width(nodes.len));
+        Self { nodes, tree_hashes }
+    }
+}
+
+impl std::fmt::Debug for NodeStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for NodeStorage {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.changed_nodes(other).is_empty()
+    }
+}
+
+impl Eq for NodeStorage {}
+
+impl std::hash::Hash for NodeStorage {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        self.iter().for_each(|node| node.hash(state));
+    }
+}
+
+/// Length of a node encoded as `optional<Node>`
+fn optional_node_len(node: Option<&TreeNode>) -> usize {
+    1 + node
+        .map(tls_codec::Size::tls_serialized_len)
+        .unwrap_or_default()
+}
+
+/// Encoded exactly like `Vec<Option<TreeNode>>`
+impl tls_codec::Size for NodeStorage {
+    fn tls_serialized_len(&self) -> usize {
+        crate::tlspl::tls_serialized_len_as_vlvec(self.iter().map(optional_node_len).sum())
+    }
+}
+
+impl tls_codec::Serialize for NodeStorage {
+    fn tls_serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<usize, tls_codec::Error> {
+        let content_len = self.iter().map(optional_node_len).sum();
+        let mut written = tls_codec::vlen::write_length(writer, content_len)?;
+        for node in self.iter() {
+            written += match node {
+                Some(node) => {
+                    writer.write_all(&[1])?;
+                    1 + node.tls_serialize(writer)?
+                }
+                None => {
+                    writer.write_all(&[0])?;
+                    1
+                }
+            };
+        }
+        Ok(written)
+    }
+}
+
+impl tls_codec::Deserialize for NodeStorage {
+    fn tls_deserialize<R: std::io::Read>(bytes: &mut R) -> Result<Self, tls_codec::Error>
+    where
+        Self: Sized,
+    {
+        Vec::<Option<TreeNode>>::tls_deserialize(bytes).map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::ParentNode;
+    use tls_codec::Serialize as _;
+
+    fn node(x: usize) -> Option<TreeNode> {
+        (x % 3 != 0).then(|| {
+            ParentNode {
+                encryption_key: x.to_be_bytes().to_vec().into(),
+                parent_hash: vec![].into(),
+                unmerged_leaves: vec![],
+            }
+            .into()
+        })
+    }
+
+    #[test]
+    fn copy_on_write_across_chunks() {
+        let nodes: Vec<_> = (0..CHUNK_LEN * 2 + 7).map(node).collect();
+        let storage = NodeStorage::from(nodes.clone());
+        assert_eq!(
+            storage.tls_serialize_detached().unwrap(),
+            nodes.tls_serialize_detached().unwrap()
+        );
+
+        let mut updated = storage.clone();
+        assert!(storage.changed_nodes(&updated).is_empty());
+        updated.replace(CHUNK_LEN + 1, None);
+        updated.get_mut(CHUNK_LEN + 3).unwrap();
+        updated.resize(CHUNK_LEN * 3 + 1);
+        assert_eq!(updated.len(), CHUNK_LEN * 3 + 1);
+        assert_eq!(
+            storage.changed_nodes(&updated),
+            vec![(CHUNK_LEN + 1) as NodeIndex]
+        );
+        assert!(Arc::ptr_eq(
+            &storage.nodes.chunks[0],
+            &updated.nodes.chunks[0]
+        ));
+
+        updated.replace(CHUNK_LEN * 3 + 5, node(1));
+        updated.resize(CHUNK_LEN - 1);
+        assert_eq!(updated.iter().count(), CHUNK_LEN - 1);
+        assert_eq!(updated.into_vec(), nodes[..CHUNK_LEN - 1]);
+        assert_eq!(storage.into_vec(), nodes);
+    }
+
+    #[test]
+    fn tree_hash_invalidation() {
+        let nodes: Vec<_> = (0..CHUNK_LEN * 2 + 7).map(node).collect();
+        let mut storage = NodeStorage::from(nodes);
+        let width = storage.tree_hashes.len;
+        assert_eq!(width, math::node_width(512) as usize);
+
+        let ciphersuite = CiphersuiteId::default();
+        for x in 0..width as NodeIndex {
+            storage.cache_tree_hash(x, ciphersuite, vec![].into());
+        }
+        let snapshot = storage.clone();
+
+        let leaf = 200;
+        storage.replace(math::leaf_to_node(leaf) as usize, None);
+        let direct_path = math::direct_path(math::leaf_to_node(leaf), 512);
+        for x in 0..width as NodeIndex {
+            let invalidated = x == math::leaf_to_node(leaf) || direct_path.contains(&x);
+            assert_eq!(
+                storage.cached_tree_hash(x, ciphersuite).is_none(),
+                invalidated
+            );
+            assert!(snapshot.cached_tree_hash(x, ciphersuite).is_some());
+        }
+
+        // Hashes cached after a change must not end up in trees sharing the storage, even
+        // when the invalidated hashes weren't cached yet
+        let root = math::root(512);
+        let before = storage.clone();
+        storage.replace(math::leaf_to_node(leaf) as usize, node(1));
+        storage.cache_tree_hash(root, ciphersuite, vec![1].into());
+        assert!(before.cached_tree_hash(root, ciphersuite).is_none());
+    }
+}
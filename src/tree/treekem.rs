@@ -0,0 +1,592 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    MlsSpecError, MlsSpecResult, SensitiveBytes, Serializable as _,
+    crypto::{CipherSuiteProvider, HpkeKeyPair, HpkePrivateKey, HpkePublicKey, LabeledCrypto as _},
+    defs::{
+        LeafIndex, NodeIndex,
+        labels::{KdfLabelKind, PublicKeyEncryptionLabel},
+    },
+    key_schedule::GroupContext,
+    tree::{
+        ParentNode, RatchetTree, TreeNode, UpdatePath, UpdatePathNode,
+        leaf_node::{LeafNode, LeafNodeMemberInfo, LeafNodeSource},
+        math,
+    },
+};
+
+/// Private TreeKEM state of a member: the private key of its leaf and the path secrets
+/// of the parent nodes it knows about
+///
+/// <https://www.rfc-editor.org/rfc/rfc9420.html#section-7.4>
+#[derive(Clone)]
+pub struct TreeKemPrivate {
+    pub leaf_index: LeafIndex,
+    pub encryption_key: HpkePrivateKey,
+    pub path_secrets: BTreeMap<NodeIndex, SensitiveBytes>,
+}
+
+impl std::fmt::Debug for TreeKemPrivate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TreeKemPrivate")
+            .field("leaf_index", &self.leaf_index)
+            .field("path_secrets", &self.path_secrets.keys())
+            .finish_non_exhaustive()
+    }
+}
+
+impl TreeKemPrivate {
+    pub fn new(leaf_index: LeafIndex, encryption_key: HpkePrivateKey) -> Self {
+        Self {
+            leaf_index,
+            encryption_key,
+            path_secrets: BTreeMap::new(),
+        }
+    }
+
+    /// Derives the key pair of a parent node from its path secret
+    ///
+    /// `node_secret = DeriveSecret(path_secret, "node")`, `node_priv, node_pub = KEM.DeriveKeyPair(node_secret)`
+    pub fn node_key_pair(
+        provider: &impl CipherSuiteProvider,
+        path_secret: &[u8],
+    ) -> MlsSpecResult<HpkeKeyPair> {
+        let node_secret = provider.derive_secret(path_secret, &KdfLabelKind::Node)?;
+        provider.kem_derive(node_secret.as_slice())
+    }
+
+    /// Returns the private key of node `x` if known
+    fn private_key(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        x: NodeIndex,
+    ) -> MlsSpecResult<Option<HpkePrivateKey>> {
+        if x == math::leaf_to_node(self.leaf_index) {
+            return Ok(Some(self.encryption_key.clone()));
+        }
+
+        let Some(path_secret) = self.path_secrets.get(&x) else {
+            return Ok(None);
+        };
+        let mut key_pair = Self::node_key_pair(provider, path_secret.as_slice())?;
+        Ok(Some(key_pair.extract_secret_key()))
+    }
+}
+
+/// Output of [RatchetTree::generate_update_path]
+#[derive(Debug, Clone)]
+pub struct GeneratedUpdatePath {
+    pub update_path: UpdatePath,
+    /// New private TreeKEM state of the committer
+    pub private: TreeKemPrivate,
+    pub commit_secret: SensitiveBytes,
+}
+
+impl RatchetTree {
+    /// Generates a fresh UpdatePath for the `sender` leaf and merges it into the tree.
+    ///
+    /// The encryption key, source and signature of `leaf_node` are replaced, while its other fields are kept.
+    /// `group_context` is the provisional GroupContext of the new epoch, whose `tree_hash` gets updated
+    /// to the one of the tree with the UpdatePath merged. Path secrets aren't encrypted to the `excluded` leaves,
+    /// which are typically the ones added by the same Commit.
+    ///
+    /// Neither the tree nor `group_context` are modified on failure.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-7.5>
+    pub fn generate_update_path(
+        &mut self,
+        provider: &impl CipherSuiteProvider,
+        sender: LeafIndex,
+        mut leaf_node: LeafNode,
+        signature_private_key: &[u8],
+        group_context: &mut GroupContext,
+        excluded: &[LeafIndex],
+    ) -> MlsSpecResult<GeneratedUpdatePath> {
+        if self.leaf_node(sender).is_none() {
+            return Err(MlsSpecError::RatchetTreeBlankLeaf(sender));
+        }
+
+        let filtered_direct_path = self.filtered_direct_path_with_copath(sender);
+        let mut leaf_key_pair = provider.kem_generate()?;
+        let mut private = TreeKemPrivate::new(sender, leaf_key_pair.extract_secret_key());
+
+        let mut path_secret = provider.random_bytes_vec(provider.kdf_extract_size())?;
+        let mut path_public_keys = Vec::with_capacity(filtered_direct_path.len());
+        for (x, _) in &filtered_direct_path {
+            let mut key_pair = TreeKemPrivate::node_key_pair(provider, path_secret.as_slice())?;
+            path_public_keys.push(key_pair.extract_public_key());
+            let next_path_secret =
+                provider.derive_secret(path_secret.as_slice(), &KdfLabelKind::Path)?;
+            private.path_secrets.insert(*x, path_secret);
+            path_secret = next_path_secret;
+        }
+        let commit_secret = path_secret;
+
+        let mut tree = self.clone();
+        tree.merge_path(provider, sender, &filtered_direct_path, &path_public_keys)?;
+        leaf_node.encryption_key = leaf_key_pair.extract_public_key();
+        leaf_node.source = LeafNodeSource::Commit {
+            parent_hash: tree.leaf_parent_hash(provider, &filtered_direct_path)?,
+        };
+        leaf_node.sign(
+            provider,
+            signature_private_key,
+            Some(LeafNodeMemberInfo {
+                group_id: group_context.group_id(),
+                leaf_index: sender,
+            }),
+        )?;
+        tree.set_node(math::leaf_to_node(sender), leaf_node.clone().into());
+
+        let mut provisional_group_context = group_context.clone();
+        provisional_group_context.tree_hash = tree.root_tree_hash(provider)?;
+        let context = provisional_group_context.to_tls_bytes()?;
+
+        let nodes = filtered_direct_path
+            .iter()
+            .zip(path_public_keys)
+            .map(|((x, copath_child), encryption_key)| {
+                let path_secret = &private.path_secrets[x];
+                let encrypted_path_secret = tree
+                    .recipients(*copath_child, excluded)
+                    .into_iter()
+                    .map(|recipient| {
+                        provider.encrypt_with_label(
+                            tree.public_key(recipient)?,
+                            &PublicKeyEncryptionLabel::UpdatePathNode,
+                            &context,
+                            path_secret.as_slice(),
+                        )
+                    })
+                    .collect::<MlsSpecResult<_>>()?;
+                Ok(UpdatePathNode {
+                    encryption_key,
+                    encrypted_path_secret,
+                })
+            })
+            .collect::<MlsSpecResult<_>>()?;
+
+        *self = tree;
+        *group_context = provisional_group_context;
+        Ok(GeneratedUpdatePath {
+            update_path: UpdatePath { leaf_node, nodes },
+            private,
+            commit_secret,
+        })
+    }
+
+    /// Merges the UpdatePath sent by `sender` into the tree, decrypts the path secret meant
+    /// for the member owning `private` and derives the path secrets above it.
+    ///
+    /// `group_context` is the provisional GroupContext of the new epoch, whose `tree_hash` gets updated
+    /// to the one of the tree with the UpdatePath merged. `excluded` are the leaves the sender didn't encrypt
+    /// path secrets to, i.e. the ones added by the same Commit.
+    ///
+    /// Returns the `commit_secret`. Neither the tree nor `private` are modified on failure.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-12.4.2>
+    pub fn apply_update_path(
+        &mut self,
+        provider: &impl CipherSuiteProvider,
+        sender: LeafIndex,
+        update_path: &UpdatePath,
+        group_context: &mut GroupContext,
+        excluded: &[LeafIndex],
+        private: &mut TreeKemPrivate,
+    ) -> MlsSpecResult<SensitiveBytes> {
+        if self.leaf_node(sender).is_none() {
+            return Err(MlsSpecError::RatchetTreeBlankLeaf(sender));
+        }
+
+        let filtered_direct_path = self.filtered_direct_path_with_copath(sender);
+        if update_path.nodes.len() != filtered_direct_path.len() {
+            return Err(MlsSpecError::InvalidUpdatePathLength {
+                expected: filtered_direct_path.len(),
+                actual: update_path.nodes.len(),
+            });
+        }
+
+        let receiver = math::leaf_to_node(private.leaf_index);
+        let mut decryption = None;
+        for (position, ((_, copath_child), node)) in filtered_direct_path
+            .iter()
+            .zip(&update_path.nodes)
+            .enumerate()
+        {
+            let recipients = self.recipients(*copath_child, excluded);
+            if node.encrypted_path_secret.len() != recipients.len() {
+                return Err(MlsSpecError::InvalidUpdatePathLength {
+                    expected: recipients.len(),
+                    actual: node.encrypted_path_secret.len(),
+                });
+            }
+
+            if decryption.is_none()
+                && math::common_ancestor(receiver, *copath_child) == *copath_child
+            {
+                for (index, recipient) in recipients.into_iter().enumerate() {
+                    if let Some(private_key) = private.private_key(provider, recipient)? {
+                        let public_key = self.public_key(recipient)?.clone();
+                        decryption = Some((position, index, private_key, public_key));
+                        break;
+                    }
+                }
+            }
+        }
+        let (position, index, private_key, public_key) =
+            decryption.ok_or(MlsSpecError::TreeKemMissingPrivateKey)?;
+
+        let leaf_node = &update_path.leaf_node;
+        leaf_node.verify_signature(
+            provider,
+            Some(LeafNodeMemberInfo {
+                group_id: group_context.group_id(),
+                leaf_index: sender,
+            }),
+        )?;
+
+        let mut tree = self.clone();
+        let path_public_keys = update_path
+            .nodes
+            .iter()
+            .map(|node| node.encryption_key.clone())
+            .collect::<Vec<_>>();
+        tree.merge_path(provider, sender, &filtered_direct_path, &path_public_keys)?;
+        let parent_hash = tree.leaf_parent_hash(provider, &filtered_direct_path)?;
+        if !leaf_node
+            .parent_hash()
+            .is_some_and(|leaf_parent_hash| parent_hash.ct_eq_slice(leaf_parent_hash))
+        {
+            return Err(MlsSpecError::InvalidParentHash(math::leaf_to_node(sender)));
+        }
+        tree.set_node(math::leaf_to_node(sender), leaf_node.clone().into());
+
+        let tree_hash = tree.root_tree_hash(provider)?;
+        let mut provisional_group_context = group_context.clone();
+        provisional_group_context.tree_hash = tree_hash;
+        let mut path_secret = provider.decrypt_with_label(
+            &private_key,
+            &public_key,
+            &PublicKeyEncryptionLabel::UpdatePathNode,
+            &provisional_group_context.to_tls_bytes()?,
+            &update_path.nodes[position].encrypted_path_secret[index],
+        )?;
+
+        let mut path_secrets = BTreeMap::new();
+        for ((x, _), node) in filtered_direct_path
+            .iter()
+            .zip(&update_path.nodes)
+            .skip(position)
+        {
+            let key_pair = TreeKemPrivate::node_key_pair(provider, path_secret.as_slice())?;
+            if !key_pair.pk.ct_eq_slice(node.encryption_key.as_slice()) {
+                return Err(MlsSpecError::UpdatePathKeyMismatch(*x));
+            }
+            let next_path_secret =
+                provider.derive_secret(path_secret.as_slice(), &KdfLabelKind::Path)?;
+            path_secrets.insert(*x, path_secret);
+            path_secret = next_path_secret;
+        }
+
+        *self = tree;
+        *group_context = provisional_group_context;
+        for x in math::direct_path(math::leaf_to_node(sender), self.leaf_count()) {
+            private.path_secrets.remove(&x);
+        }
+        private.path_secrets.append(&mut path_secrets);
+
+        Ok(path_secret)
+    }
+
+    /// Blanks the direct path of `sender` and sets the new parent nodes of its filtered direct path,
+    /// along with their parent hashes
+    fn merge_path(
+        &mut self,
+        provider: &impl CipherSuiteProvider,
+        sender: LeafIndex,
+        filtered_direct_path: &[(NodeIndex, NodeIndex)],
+        path_public_keys: &[HpkePublicKey],
+    ) -> MlsSpecResult<()> {
+        self.blank_direct_path(sender);
+        for ((x, _), encryption_key) in filtered_direct_path.iter().zip(path_public_keys) {
+            self.set_node(
+                *x,
+                ParentNode {
+                    encryption_key: encryption_key.clone(),
+                    parent_hash: vec![].into(),
+                    unmerged_leaves: vec![],
+                }
+                .into(),
+            );
+        }
+
+        // Parent hashes are computed from the root downwards
+        for (i, (x, _)) in filtered_direct_path.iter().enumerate().rev() {
+            let parent_hash = match filtered_direct_path.get(i + 1) {
+                Some((parent, copath_child)) => {
+                    self.parent_hash(provider, *parent, *copath_child)?
+                }
+                None => vec![].into(),
+            };
+            if let Some(TreeNode::ParentNode(parent_node)) = self.node_mut(*x) {
+                parent_node.parent_hash = parent_hash;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parent hash to be set in the LeafNode at the bottom of `filtered_direct_path`
+    fn leaf_parent_hash(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        filtered_direct_path: &[(NodeIndex, NodeIndex)],
+    ) -> MlsSpecResult<SensitiveBytes> {
+        match filtered_direct_path.first() {
+            Some((parent, copath_child)) => self.parent_hash(provider, *parent, *copath_child),
+            None => Ok(vec![].into()),
+        }
+    }
+
+    /// Nodes a path secret is encrypted to, i.e. the resolution of `copath_child` without the `excluded` leaves
+    fn recipients(&self, copath_child: NodeIndex, excluded: &[LeafIndex]) -> Vec<NodeIndex> {
+        self.resolution(copath_child)
+            .into_iter()
+            .filter(|x| math::node_to_leaf(*x).is_none_or(|leaf| !excluded.contains(&leaf)))
+            .collect()
+    }
+
+    fn public_key(&self, x: NodeIndex) -> MlsSpecResult<&HpkePublicKey> {
+        match self.node(x) {
+            Some(TreeNode::LeafNode(leaf_node)) => Ok(&leaf_node.encryption_key),
+            Some(TreeNode::ParentNode(parent_node)) => Ok(&parent_node.encryption_key),
+            None => Err(MlsSpecError::RatchetTreeBlankNode(x)),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "rustcrypto"))]
+mod tests {
+    use super::*;
+    use crate::test_utils::fixtures;
+
+    struct Commit {
+        /// Tree and GroupContext of the members before the Commit
+        tree: RatchetTree,
+        group_context: GroupContext,
+        /// Private state of the receiver, leaf 1
+        receiver: TreeKemPrivate,
+        /// Output of the committer, leaf 0
+        sender_leaf_node: LeafNode,
+        sender_signature_key: SensitiveBytes,
+        generated: GeneratedUpdatePath,
+    }
+
+    /// Leaf 0 commits to a group of 3 in which leaf 1 committed before
+    fn commit(provider: &impl CipherSuiteProvider) -> Commit {
+        let mut tree = RatchetTree::default();
+        let mut members = ["a", "b", "c"]
+            .map(|identity| fixtures::leaf_node(provider, identity).unwrap())
+            .into_iter();
+        let (sender_leaf_node, sender_signature_key) = members.next().unwrap();
+        let (receiver_leaf_node, receiver_signature_key) = members.next().unwrap();
+        tree.add_leaf(sender_leaf_node.clone());
+        tree.add_leaf(receiver_leaf_node.clone());
+        tree.add_leaf(members.next().unwrap().0);
+
+        let mut group_context = fixtures::group_context();
+        let receiver = tree
+            .generate_update_path(
+                provider,
+                1,
+                receiver_leaf_node,
+                &receiver_signature_key,
+                &mut group_context,
+                &[],
+            )
+            .unwrap()
+            .private;
+
+        let generated = tree
+            .clone()
+            .generate_update_path(
+                provider,
+                0,
+                sender_leaf_node.clone(),
+                &sender_signature_key,
+                &mut group_context.clone(),
+                &[],
+            )
+            .unwrap();
+
+        Commit {
+            tree,
+            group_context,
+            receiver,
+            sender_leaf_node,
+            sender_signature_key,
+            generated,
+        }
+    }
+
+    impl Commit {
+        fn apply(&self, update_path: &UpdatePath) -> MlsSpecResult<SensitiveBytes> {
+            let provider = fixtures::provider();
+            let mut tree = self.tree.clone();
+            let mut group_context = self.group_context.clone();
+            let mut receiver = self.receiver.clone();
+            let result = tree.apply_update_path(
+                &provider,
+                0,
+                update_path,
+                &mut group_context,
+                &[],
+                &mut receiver,
+            );
+            if result.is_err() {
+                assert_eq!(tree, self.tree);
+                assert_eq!(group_context, self.group_context);
+                assert_eq!(receiver.path_secrets, self.receiver.path_secrets);
+            }
+            result
+        }
+    }
+
+    #[test]
+    fn apply_update_path_roundtrip() {
+        let provider = fixtures::provider();
+        let commit = commit(&provider);
+        let commit_secret = commit.apply(&commit.generated.update_path).unwrap();
+        assert_eq!(commit_secret, commit.generated.commit_secret);
+    }
+
+    #[test]
+    fn generate_update_path_leaves_tree_untouched_on_failure() {
+        let provider = fixtures::provider();
+        let commit = commit(&provider);
+        let mut tree = commit.tree.clone();
+        let mut group_context = commit.group_context.clone();
+
+        // Signing the new LeafNode happens after the path is merged
+        assert!(
+            tree.generate_update_path(
+                &provider,
+                0,
+                commit.sender_leaf_node,
+                &[],
+                &mut group_context,
+                &[],
+            )
+            .is_err()
+        );
+        assert_eq!(tree, commit.tree);
+        assert_eq!(group_context, commit.group_context);
+    }
+
+    #[test]
+    fn apply_update_path_rejects_invalid_length() {
+        let provider = fixtures::provider();
+        let commit = commit(&provider);
+
+        let mut update_path = commit.generated.update_path.clone();
+        update_path.nodes.pop();
+        assert!(matches!(
+            commit.apply(&update_path),
+            Err(MlsSpecError::InvalidUpdatePathLength {
+                expected: 2,
+                actual: 1
+            })
+        ));
+
+        // Node 3 is encrypted to the resolution of node 5, i.e. leaf 2 alone
+        let mut update_path = commit.generated.update_path.clone();
+        let ciphertext = update_path.nodes[1].encrypted_path_secret[0].clone();
+        update_path.nodes[1].encrypted_path_secret.push(ciphertext);
+        assert!(matches!(
+            commit.apply(&update_path),
+            Err(MlsSpecError::InvalidUpdatePathLength {
+                expected: 1,
+                actual: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn apply_update_path_rejects_invalid_parent_hash() {
+        let provider = fixtures::provider();
+        let commit = commit(&provider);
+
+        // The parent hash in the LeafNode covers the keys of the whole path
+        let mut update_path = commit.generated.update_path.clone();
+        update_path.nodes[1].encryption_key = provider.kem_generate().unwrap().extract_public_key();
+        assert!(matches!(
+            commit.apply(&update_path),
+            Err(MlsSpecError::InvalidParentHash(0))
+        ));
+    }
+
+    #[test]
+    fn apply_update_path_rejects_key_mismatch() {
+        let provider = fixtures::provider();
+        let commit = commit(&provider);
+
+        // Node 3 gets a key that doesn't derive from its path secret, while the LeafNode and
+        // the ciphertext to leaf 1 are made valid for the tampered path
+        let mut update_path = commit.generated.update_path.clone();
+        update_path.nodes[1].encryption_key = provider.kem_generate().unwrap().extract_public_key();
+
+        let filtered_direct_path = commit.tree.filtered_direct_path_with_copath(0);
+        let path_public_keys = update_path
+            .nodes
+            .iter()
+            .map(|node| node.encryption_key.clone())
+            .collect::<Vec<_>>();
+        let mut tree = commit.tree.clone();
+        tree.merge_path(&provider, 0, &filtered_direct_path, &path_public_keys)
+            .unwrap();
+        update_path.leaf_node.source = LeafNodeSource::Commit {
+            parent_hash: tree
+                .leaf_parent_hash(&provider, &filtered_direct_path)
+                .unwrap(),
+        };
+        update_path
+            .leaf_node
+            .sign(
+                &provider,
+                &commit.sender_signature_key,
+                Some(LeafNodeMemberInfo {
+                    group_id: commit.group_context.group_id(),
+                    leaf_index: 0,
+                }),
+            )
+            .unwrap();
+        tree.set_node(0, update_path.leaf_node.clone().into());
+
+        let mut group_context = commit.group_context.clone();
+        group_context.tree_hash = tree.root_tree_hash(&provider).unwrap();
+        update_path.nodes[0].encrypted_path_secret[0] = provider
+            .encrypt_with_label(
+                commit.tree.public_key(2).unwrap(),
+                &PublicKeyEncryptionLabel::UpdatePathNode,
+                &group_context.to_tls_bytes().unwrap(),
+                commit.generated.private.path_secrets[&1].as_slice(),
+            )
+            .unwrap();
+
+        assert!(matches!(
+            commit.apply(&update_path),
+            Err(MlsSpecError::UpdatePathKeyMismatch(3))
+        ));
+    }
+
+    #[test]
+    fn public_key_of_blank_node() {
+        let provider = fixtures::provider();
+        let commit = commit(&provider);
+        assert!(commit.tree.node(5).is_none());
+        assert!(matches!(
+            commit.tree.public_key(5),
+            Err(MlsSpecError::RatchetTreeBlankNode(5))
+        ));
+    }
+}
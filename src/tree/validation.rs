@@ -0,0 +1,422 @@
+use std::collections::HashSet;
+
+use crate::{
+    MlsSpecError, MlsSpecResult,
+    crypto::CipherSuiteProvider,
+    defs::{CredentialType, LeafIndex, NodeIndex},
+    group::extensions::Extension,
+    key_schedule::GroupContext,
+    tree::{
+        RatchetTree, TreeNode,
+        leaf_node::{LeafNode, LeafNodeMemberInfo},
+        math,
+    },
+};
+
+impl RatchetTree {
+    /// Checks the structural invariants of the tree representation: an odd number of nodes,
+    /// no trailing blank node, and leaves (resp. parents) at even (resp. odd) indices
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-12.4.3.3>
+    pub fn validate_structure(&self) -> MlsSpecResult<()> {
+        if self.0.len() % 2 == 0 {
+            return Err(MlsSpecError::RatchetTreeEvenNodeCount(self.0.len()));
+        }
+
+        if self.0.get(self.0.len() - 1).is_none() {
+            return Err(MlsSpecError::RatchetTreeNotTrimmed);
+        }
+
+        for (x, node) in self.0.iter().enumerate() {
+            let x = x as NodeIndex;
+            let valid = match node {
+                Some(TreeNode::LeafNode(_)) => math::is_leaf(x),
+                Some(TreeNode::ParentNode(_)) => !math::is_leaf(x),
+                None => true,
+            };
+            if !valid {
+                return Err(MlsSpecError::RatchetTreeInvalidNodeType(x));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates a ratchet tree received from a Welcome or a GroupInfo:
+    ///
+    /// - The structural invariants of [Self::validate_structure]
+    /// - Unmerged leaves are non-blank descendants of their parent, and are also listed as unmerged by
+    ///   the non-blank nodes between them and their parent
+    /// - Encryption keys are unique across all nodes, and signature keys across all leaves
+    /// - Every LeafNode supports the required capabilities of `group_context`, the credential types
+    ///   of all members and its own extensions
+    /// - The signatures of all LeafNodes verify
+    /// - The tree hash matches the one of `group_context`
+    /// - Every parent node is parent-hash valid
+    ///
+    /// The lifetimes of KeyPackage-sourced leaves aren't checked, as those of members added a while ago
+    /// have legitimately expired. Credentials still have to be validated with the Authentication Service.
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-7.9>
+    pub fn validate(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        group_context: &GroupContext,
+    ) -> MlsSpecResult<()> {
+        self.validate_structure()?;
+        self.validate_unmerged_leaves()?;
+
+        let mut encryption_keys = HashSet::new();
+        for (x, node) in self.0.iter().enumerate() {
+            let encryption_key = match node {
+                Some(TreeNode::LeafNode(leaf_node)) => &leaf_node.encryption_key,
+                Some(TreeNode::ParentNode(parent_node)) => &parent_node.encryption_key,
+                None => continue,
+            };
+            if !encryption_keys.insert(encryption_key.as_slice()) {
+                return Err(MlsSpecError::DuplicateEncryptionKey(x as NodeIndex));
+            }
+        }
+
+        let mut signature_keys = HashSet::new();
+        for (leaf, leaf_node) in self.leaves() {
+            if !signature_keys.insert(leaf_node.signature_key.as_slice()) {
+                return Err(MlsSpecError::DuplicateSignatureKey(leaf));
+            }
+        }
+        self.validate_leaf_capabilities(group_context)?;
+
+        self.verify_leaf_signatures(provider, group_context)?;
+        self.verify_tree_hash(provider, group_context)?;
+        self.verify_parent_hashes(provider)
+    }
+
+    /// Verifies the signatures of all the non-blank LeafNodes, as members of the group of `group_context`.
+    ///
+    /// With the `rayon` feature, signatures are verified in parallel. Either way, the error
+    /// of the leftmost invalid leaf is returned.
+    pub fn verify_leaf_signatures(
+        &self,
+        provider: &impl CipherSuiteProvider,
+        group_context: &GroupContext,
+    ) -> MlsSpecResult<()> {
+        let verify = |(leaf_index, leaf_node): (LeafIndex, &LeafNode)| {
+            leaf_node.verify_signature(
+                provider,
+                Some(LeafNodeMemberInfo {
+                    group_id: group_context.group_id(),
+                    leaf_index,
+                }),
+            )
+        };
+
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            self.leaves()
+                .collect::<Vec<_>>()
+                .into_par_iter()
+                .map(verify)
+                .find_map_first(Result::err)
+                .map_or(Ok(()), Err)
+        }
+
+        #[cfg(not(feature = "rayon"))]
+        self.leaves().try_for_each(verify)
+    }
+
+    /// Checks the capabilities of every LeafNode against the group
+    ///
+    /// <https://www.rfc-editor.org/rfc/rfc9420.html#section-7.3>
+    fn validate_leaf_capabilities(&self, group_context: &GroupContext) -> MlsSpecResult<()> {
+        let mut credential_types = vec![];
+        for (_, leaf_node) in self.leaves() {
+            let credential_type = CredentialType::from(&leaf_node.credential);
+            if !credential_types.contains(&credential_type) {
+                credential_types.push(credential_type);
+            }
+        }
+
+        for (leaf, leaf_node) in self.leaves() {
+            let capabilities = &leaf_node.capabilities;
+            if let Some(required) = group_context.required_capabilities() {
+                let supported = required.extension_types.iter().all(|ext_type| {
+                    ext_type.is_spec_default() || capabilities.extensions.contains(ext_type)
+                }) && required.proposal_types.iter().all(|proposal_type| {
+                    proposal_type.is_spec_default()
+                        || capabilities.proposals.contains(proposal_type)
+                }) && required
+                    .credential_types
+                    .iter()
+                    .all(|credential_type| capabilities.credentials.contains(credential_type));
+                if !supported {
+                    return Err(MlsSpecError::UnsupportedRequiredCapabilities(leaf));
+                }
+            }
+
+            if let Some(credential_type) = credential_types
+                .iter()
+                .find(|credential_type| !capabilities.credentials.contains(credential_type))
+            {
+                return Err(MlsSpecError::UnsupportedCredentialType {
+                    leaf,
+                    credential_type: *credential_type,
+                });
+            }
+
+            if let Some(unsupported) =
+                leaf_node
+                    .extensions
+                    .iter()
+                    .map(Extension::ext_type)
+                    .find(|ext_type| {
+                        !ext_type.is_spec_default() && !capabilities.extensions.contains(ext_type)
+                    })
+            {
+                return Err(MlsSpecError::UnsupportedExtension(unsupported));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_unmerged_leaves(&self) -> MlsSpecResult<()> {
+        let leaf_count = self.leaf_count();
+        for (x, node) in self.0.iter().enumerate() {
+            let Some(TreeNode::ParentNode(parent_node)) = node else {
+                continue;
+            };
+            let parent = x as NodeIndex;
+
+            for leaf in &parent_node.unmerged_leaves {
+                let leaf_x = math::leaf_to_node(*leaf);
+                let invalid = MlsSpecError::InvalidUnmergedLeaf {
+                    parent,
+                    leaf: *leaf,
+                };
+                if self.leaf_node(*leaf).is_none() {
+                    return Err(invalid);
+                }
+
+                let intermediates = math::direct_path(leaf_x, leaf_count);
+                let Some(position) = intermediates.iter().position(|p| *p == parent) else {
+                    return Err(invalid);
+                };
+                let consistent = intermediates[..position].iter().all(|p| {
+                    self.parent_node(*p)
+                        .is_none_or(|intermediate| intermediate.unmerged_leaves.contains(leaf))
+                });
+                if !consistent {
+                    return Err(invalid);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "rustcrypto"))]
+mod tests {
+    use super::*;
+    use crate::{
+        Parsable as _, Serializable as _,
+        credential::{Credential, X509Credential},
+        crypto::CipherSuiteProvider,
+        defs::ExtensionType,
+        group::{RequiredCapabilities, extensions::ArbitraryExtension},
+        test_utils::fixtures,
+    };
+
+    /// Valid tree of 4 members, along with the matching GroupContext
+    fn valid_tree(provider: &impl CipherSuiteProvider) -> (RatchetTree, GroupContext) {
+        let tree = fixtures::committed_tree(provider, &["a", "b", "c", "d"], &[]).unwrap();
+        let mut group_context = fixtures::group_context();
+        group_context.tree_hash = tree.root_tree_hash(provider).unwrap();
+        tree.validate(provider, &group_context).unwrap();
+        (tree, group_context)
+    }
+
+    fn leaf_mut(tree: &mut RatchetTree, leaf: LeafIndex) -> &mut LeafNode {
+        tree.node_mut(math::leaf_to_node(leaf))
+            .and_then(TreeNode::as_leaf_node_mut)
+            .unwrap()
+    }
+
+    #[test]
+    fn validate_rejects_duplicate_signature_keys() {
+        let provider = fixtures::provider();
+        let (mut tree, group_context) = valid_tree(&provider);
+        let signature_key = tree.leaf_node(1).unwrap().signature_key.clone();
+        leaf_mut(&mut tree, 3).signature_key = signature_key;
+        assert!(matches!(
+            tree.validate(&provider, &group_context),
+            Err(MlsSpecError::DuplicateSignatureKey(3))
+        ));
+    }
+
+    #[test]
+    fn validate_checks_required_capabilities() {
+        let provider = fixtures::provider();
+        let (tree, mut group_context) = valid_tree(&provider);
+        let required = |credential_types| {
+            Extension::RequiredCapabilities(RequiredCapabilities {
+                credential_types,
+                ..Default::default()
+            })
+        };
+
+        group_context.extensions = vec![required(vec![CredentialType::default()])];
+        tree.validate(&provider, &group_context).unwrap();
+
+        let x509 = CredentialType::new_unchecked(CredentialType::X509);
+        group_context.extensions = vec![required(vec![x509])];
+        assert!(matches!(
+            tree.validate(&provider, &group_context),
+            Err(MlsSpecError::UnsupportedRequiredCapabilities(0))
+        ));
+    }
+
+    #[test]
+    fn validate_checks_credential_types_of_members() {
+        let provider = fixtures::provider();
+        let (mut tree, group_context) = valid_tree(&provider);
+        let x509 = CredentialType::new_unchecked(CredentialType::X509);
+
+        // Leaf 0 doesn't support the X509 credential of leaf 2
+        leaf_mut(&mut tree, 2).credential = Credential::X509(X509Credential {
+            certificates: vec![],
+        });
+        leaf_mut(&mut tree, 2).capabilities.credentials.push(x509);
+        assert!(matches!(
+            tree.validate(&provider, &group_context),
+            Err(MlsSpecError::UnsupportedCredentialType { leaf: 0, credential_type })
+                if credential_type == x509
+        ));
+
+        // Leaf 2 doesn't support the basic credentials of the others
+        leaf_mut(&mut tree, 2).capabilities.credentials = vec![x509];
+        for leaf in [0, 1, 3] {
+            leaf_mut(&mut tree, leaf)
+                .capabilities
+                .credentials
+                .push(x509);
+        }
+        assert!(matches!(
+            tree.validate(&provider, &group_context),
+            Err(MlsSpecError::UnsupportedCredentialType { leaf: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn validate_checks_leaf_extensions_support() {
+        let provider = fixtures::provider();
+        let (mut tree, group_context) = valid_tree(&provider);
+        let extension_id = ExtensionType::new_unchecked(0xFF00);
+        leaf_mut(&mut tree, 1)
+            .extensions
+            .push(Extension::Arbitrary(ArbitraryExtension {
+                extension_id,
+                extension_data: vec![],
+            }));
+        assert!(matches!(
+            tree.validate(&provider, &group_context),
+            Err(MlsSpecError::UnsupportedExtension(ext_type)) if ext_type == extension_id
+        ));
+    }
+
+    #[test]
+    fn validate_binds_leaf_signatures_to_group() {
+        let provider = fixtures::provider();
+        let (tree, group_context) = valid_tree(&provider);
+        let mut other_group_context = GroupContext::with_group_id(b"other group".to_vec());
+        other_group_context.cipher_suite = group_context.cipher_suite;
+        other_group_context.tree_hash = group_context.tree_hash.clone();
+        assert!(matches!(
+            tree.validate(&provider, &other_group_context),
+            Err(MlsSpecError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn validate_rejects_duplicate_encryption_keys() {
+        let provider = fixtures::provider();
+        let (mut tree, group_context) = valid_tree(&provider);
+        let encryption_key = tree.leaf_node(0).unwrap().encryption_key.clone();
+        leaf_mut(&mut tree, 2).encryption_key = encryption_key;
+        assert!(matches!(
+            tree.validate(&provider, &group_context),
+            Err(MlsSpecError::DuplicateEncryptionKey(_))
+        ));
+    }
+
+    #[test]
+    fn leaf_signature_errors_are_reported_in_leaf_order() {
+        let provider = fixtures::provider();
+        let (mut tree, group_context) = valid_tree(&provider);
+        let mut tampered = tree.leaf_node(1).unwrap().signature.as_slice().to_vec();
+        tampered[0] ^= 0xFF;
+        leaf_mut(&mut tree, 1).signature = tampered.clone().into();
+        leaf_mut(&mut tree, 3).signature_key = vec![0; 3].into();
+        assert!(matches!(
+            tree.verify_leaf_signatures(&provider, &group_context),
+            Err(MlsSpecError::InvalidSignature)
+        ));
+
+        let (mut tree, _) = valid_tree(&provider);
+        leaf_mut(&mut tree, 1).signature_key = vec![0; 3].into();
+        leaf_mut(&mut tree, 3).signature = tampered.into();
+        assert!(matches!(
+            tree.verify_leaf_signatures(&provider, &group_context),
+            Err(MlsSpecError::CryptoProviderError(_))
+        ));
+    }
+
+    #[test]
+    fn decode_rejects_empty_tree() {
+        // A zero-length vector of optional nodes
+        let empty = [0x00];
+        assert!(Vec::<Option<TreeNode>>::from_tls_bytes(&empty).is_ok());
+        assert!(matches!(
+            RatchetTree::from_tls_bytes(&empty),
+            Err(MlsSpecError::TlsCodecError(_))
+        ));
+        assert!(matches!(
+            RatchetTree::default().validate_structure(),
+            Err(MlsSpecError::RatchetTreeEvenNodeCount(0))
+        ));
+    }
+
+    #[test]
+    fn decode_rejects_malformed_trees() {
+        let provider = fixtures::provider();
+        let (tree, _) = valid_tree(&provider);
+        let nodes = tree.into_inner();
+        let decode = |nodes: Vec<Option<TreeNode>>| {
+            RatchetTree::from_tls_bytes(&RatchetTree::from(nodes).to_tls_bytes().unwrap())
+        };
+
+        let mut untrimmed = nodes.clone();
+        untrimmed.extend([None, None]);
+        assert!(matches!(
+            decode(untrimmed),
+            Err(MlsSpecError::TlsCodecError(_))
+        ));
+
+        assert!(matches!(
+            decode(nodes[..nodes.len() - 1].to_vec()),
+            Err(MlsSpecError::TlsCodecError(_))
+        ));
+
+        let mut misplaced = nodes.clone();
+        misplaced[0] = nodes[1].clone();
+        assert!(matches!(
+            RatchetTree::from(misplaced.clone()).validate_structure(),
+            Err(MlsSpecError::RatchetTreeInvalidNodeType(0))
+        ));
+        assert!(matches!(
+            decode(misplaced),
+            Err(MlsSpecError::TlsCodecError(_))
+        ));
+    }
+}
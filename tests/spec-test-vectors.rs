@@ -8,6 +8,11 @@ pub trait TestVector: serde::de::DeserializeOwned {
 
     async fn execute(self) -> Result<()>;
 
+    /// Allows vectors to be marked as ignored, i.e. when they target an unsupported ciphersuite
+    fn is_supported(&self) -> bool {
+        true
+    }
+
     fn collect_tests() -> Result<Vec<Trial>>
     where
         Self: Sized + Send + 'static,
@@ -30,6 +35,7 @@ pub trait TestVector: serde::de::DeserializeOwned {
                 )
                 .to_case(Case::Snake);
 
+                let ignored = !test.is_supported();
                 Trial::test(test_name, move || {
                     tokio::runtime::Builder::new_current_thread()
                         .enable_all()
@@ -39,6 +45,7 @@ pub trait TestVector: serde::de::DeserializeOwned {
                     Ok(())
                 })
                 .with_kind("kat-test-vector")
+                .with_ignored_flag(ignored)
             })
             .collect();
 
@@ -67,6 +74,18 @@ fn main() -> Result<()> {
     let mut tests = vec![];
     tests.append(&mut deserialization::DeserializationVector::collect_tests()?);
     tests.append(&mut messages::MessagesVector::collect_tests()?);
+    tests.append(&mut hpke::HpkeVector::collect_tests()?);
+    tests.append(&mut crypto_basics::CryptoBasicsVector::collect_tests()?);
+    tests.append(&mut welcome::WelcomeVector::collect_tests()?);
+    tests.append(&mut key_schedule::KeyScheduleVector::collect_tests()?);
+    tests.append(&mut psk_secret::PskSecretVector::collect_tests()?);
+    tests.append(&mut secret_tree::SecretTreeVector::collect_tests()?);
+    tests.append(&mut transcript_hashes::TranscriptHashesVector::collect_tests()?);
+    tests.append(&mut message_protection::MessageProtectionVector::collect_tests()?);
+    tests.append(&mut tree_math::TreeMathVector::collect_tests()?);
+    tests.append(&mut tree_operations::TreeOperationsVector::collect_tests()?);
+    tests.append(&mut tree_validation::TreeValidationVector::collect_tests()?);
+    tests.append(&mut treekem::TreeKemVector::collect_tests()?);
 
     libtest_mimic::run(&args, tests).exit_if_failed();
     Ok(())
@@ -77,3 +96,39 @@ mod messages;
 
 #[path = "spec-test-vectors/deserialization.rs"]
 mod deserialization;
+
+#[path = "spec-test-vectors/hpke.rs"]
+mod hpke;
+
+#[path = "spec-test-vectors/crypto_basics.rs"]
+mod crypto_basics;
+
+#[path = "spec-test-vectors/welcome.rs"]
+mod welcome;
+
+#[path = "spec-test-vectors/key_schedule.rs"]
+mod key_schedule;
+
+#[path = "spec-test-vectors/psk_secret.rs"]
+mod psk_secret;
+
+#[path = "spec-test-vectors/secret_tree.rs"]
+mod secret_tree;
+
+#[path = "spec-test-vectors/transcript_hashes.rs"]
+mod transcript_hashes;
+
+#[path = "spec-test-vectors/message_protection.rs"]
+mod message_protection;
+
+#[path = "spec-test-vectors/tree_math.rs"]
+mod tree_math;
+
+#[path = "spec-test-vectors/tree_operations.rs"]
+mod tree_operations;
+
+#[path = "spec-test-vectors/tree_validation.rs"]
+mod tree_validation;
+
+#[path = "spec-test-vectors/treekem.rs"]
+mod treekem;
@@ -0,0 +1,235 @@
+use color_eyre::eyre::Result;
+use mls_spec::{
+    Serializable as _, ToPrefixedLabel as _,
+    crypto::{
+        CipherSuiteProvider as _, CryptoProvider as _, HpkeCiphertext, LabeledCrypto as _,
+        SignContent, rustcrypto::RustCryptoProvider,
+    },
+    defs::{
+        CiphersuiteId, ProtocolVersion,
+        labels::{HashReferenceKind, KdfLabelKind, PublicKeyEncryptionLabel, SignatureLabel},
+    },
+    test_utils::assertions::assert_eq_err,
+};
+
+#[derive(Debug, serde::Deserialize)]
+pub struct CryptoBasicsVector {
+    pub cipher_suite: CiphersuiteId,
+    pub ref_hash: RefHashVector,
+    pub expand_with_label: ExpandWithLabelVector,
+    pub derive_secret: DeriveSecretVector,
+    pub derive_tree_secret: DeriveTreeSecretVector,
+    pub sign_with_label: SignWithLabelVector,
+    pub encrypt_with_label: EncryptWithLabelVector,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct RefHashVector {
+    pub label: String,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub value: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub out: Vec<u8>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct ExpandWithLabelVector {
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub secret: Vec<u8>,
+    pub label: String,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub context: Vec<u8>,
+    pub length: u16,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub out: Vec<u8>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct DeriveSecretVector {
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub secret: Vec<u8>,
+    pub label: String,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub out: Vec<u8>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct DeriveTreeSecretVector {
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub secret: Vec<u8>,
+    pub label: String,
+    pub generation: u32,
+    pub length: u16,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub out: Vec<u8>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct SignWithLabelVector {
+    #[serde(rename = "priv", with = "faster_hex::nopfx_ignorecase")]
+    pub private: Vec<u8>,
+    #[serde(rename = "pub", with = "faster_hex::nopfx_ignorecase")]
+    pub public: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub content: Vec<u8>,
+    pub label: String,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub signature: Vec<u8>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct EncryptWithLabelVector {
+    #[serde(rename = "priv", with = "faster_hex::nopfx_ignorecase")]
+    pub private: Vec<u8>,
+    #[serde(rename = "pub", with = "faster_hex::nopfx_ignorecase")]
+    pub public: Vec<u8>,
+    pub label: String,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub context: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub plaintext: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub kem_output: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub ciphertext: Vec<u8>,
+}
+
+#[async_trait::async_trait(?Send)]
+impl super::TestVector for CryptoBasicsVector {
+    const TEST_FILE: &'static str = "crypto-basics.json";
+
+    fn is_supported(&self) -> bool {
+        RustCryptoProvider.supports_ciphersuite(self.cipher_suite)
+    }
+
+    async fn execute(self) -> Result<()> {
+        let provider = RustCryptoProvider.cipher_suite_provider(self.cipher_suite)?;
+        let protocol_version = ProtocolVersion::default();
+
+        // RefHash - the label is used verbatim, the "MLS 1.0" prefix is only part of the `KeyPackageRef` / `ProposalRef` labels
+        let label = HashReferenceKind::TestVectorRefHash;
+        assert_eq_err!(label.to_string(), self.ref_hash.label);
+        let out = provider.ref_hash(&label.to_string(), &self.ref_hash.value)?;
+        assert_eq_err!(out.as_slice(), &self.ref_hash.out[..], "RefHash");
+
+        // ExpandWithLabel
+        let label = KdfLabelKind::TestVectorExpandWithLabel;
+        assert_eq_err!(label.to_string(), self.expand_with_label.label);
+        let out = provider.expand_with_label(
+            &self.expand_with_label.secret,
+            &label,
+            &self.expand_with_label.context,
+            self.expand_with_label.length,
+        )?;
+        assert_eq_err!(
+            out.as_slice(),
+            &self.expand_with_label.out[..],
+            "ExpandWithLabel"
+        );
+        let out = provider.expand_with_label(
+            &self.expand_with_label.secret,
+            &KdfLabelKind::Arbitrary(self.expand_with_label.label.clone()),
+            &self.expand_with_label.context,
+            self.expand_with_label.length,
+        )?;
+        assert_eq_err!(
+            out.as_slice(),
+            &self.expand_with_label.out[..],
+            "ExpandWithLabel (arbitrary label)"
+        );
+
+        // DeriveSecret
+        let label = KdfLabelKind::TestVectorDeriveSecret;
+        assert_eq_err!(label.to_string(), self.derive_secret.label);
+        let out = provider.derive_secret(&self.derive_secret.secret, &label)?;
+        assert_eq_err!(out.as_slice(), &self.derive_secret.out[..], "DeriveSecret");
+
+        // DeriveTreeSecret
+        let label = KdfLabelKind::TestVectorDeriveTreeSecret;
+        assert_eq_err!(label.to_string(), self.derive_tree_secret.label);
+        let out = provider.derive_tree_secret(
+            &self.derive_tree_secret.secret,
+            &label,
+            self.derive_tree_secret.generation,
+            self.derive_tree_secret.length,
+        )?;
+        assert_eq_err!(
+            out.as_slice(),
+            &self.derive_tree_secret.out[..],
+            "DeriveTreeSecret"
+        );
+
+        // SignWithLabel / VerifyWithLabel
+        let label = SignatureLabel::TestVectorSignWithLabel;
+        assert_eq_err!(label.to_string(), self.sign_with_label.label);
+        let sign_content = SignContent {
+            label: &label.to_prefixed_string(protocol_version),
+            content: &self.sign_with_label.content,
+        }
+        .to_tls_bytes()?;
+        assert_eq_err!(
+            provider
+                .signature_key_derive_public(&self.sign_with_label.private)?
+                .as_slice(),
+            &self.sign_with_label.public[..],
+            "Signature public key"
+        );
+        // The upstream P-384 / P-521 vectors were signed with ECDSA-SHA256 instead of the digest mandated by RFC9420,
+        // so the fixed signature can only be checked for the other ciphersuites
+        if !matches!(
+            *self.cipher_suite,
+            CiphersuiteId::MLS_256_DHKEMP521_AES256GCM_SHA512_P521
+                | CiphersuiteId::MLS_256_DHKEMP384_AES256GCM_SHA384_P384
+        ) {
+            provider.verify(
+                &self.sign_with_label.public,
+                &self.sign_with_label.signature,
+                &sign_content,
+            )?;
+        }
+        let signature = provider.sign(&self.sign_with_label.private, &sign_content)?;
+        provider.verify(&self.sign_with_label.public, &signature, &sign_content)?;
+
+        // EncryptWithLabel / DecryptWithLabel
+        let label = PublicKeyEncryptionLabel::TestVectorEncryptWithLabel;
+        assert_eq_err!(label.to_string(), self.encrypt_with_label.label);
+        let public = self.encrypt_with_label.public.clone().into();
+        let private = self.encrypt_with_label.private.clone().into();
+        let ciphertext = HpkeCiphertext {
+            kem_output: self.encrypt_with_label.kem_output.clone().into(),
+            ciphertext: self.encrypt_with_label.ciphertext.clone().into(),
+        };
+        let plaintext = provider.decrypt_with_label(
+            &private,
+            &public,
+            &label,
+            &self.encrypt_with_label.context,
+            &ciphertext,
+        )?;
+        assert_eq_err!(
+            plaintext.as_slice(),
+            &self.encrypt_with_label.plaintext[..],
+            "DecryptWithLabel"
+        );
+        let ciphertext = provider.encrypt_with_label(
+            &public,
+            &label,
+            &self.encrypt_with_label.context,
+            &self.encrypt_with_label.plaintext,
+        )?;
+        let plaintext = provider.decrypt_with_label(
+            &private,
+            &public,
+            &label,
+            &self.encrypt_with_label.context,
+            &ciphertext,
+        )?;
+        assert_eq_err!(
+            plaintext.as_slice(),
+            &self.encrypt_with_label.plaintext[..],
+            "EncryptWithLabel"
+        );
+
+        Ok(())
+    }
+}
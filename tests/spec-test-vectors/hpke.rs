@@ -0,0 +1,150 @@
+use color_eyre::eyre::Result;
+use mls_spec::{
+    crypto::rustcrypto::RustCryptoProvider,
+    crypto::{CipherSuiteProvider as _, CryptoProvider as _, HpkeCiphertext},
+    defs::CiphersuiteId,
+    test_utils::assertions::assert_eq_err,
+};
+
+/// RFC9180 test vectors, restricted to the base mode and the (KEM, KDF, AEAD) triples used by MLS ciphersuites
+#[derive(Debug, serde::Deserialize)]
+pub struct HpkeVector {
+    pub mode: u8,
+    pub kem_id: u16,
+    pub kdf_id: u16,
+    pub aead_id: u16,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub info: Vec<u8>,
+    #[serde(rename = "ikmR", with = "faster_hex::nopfx_ignorecase")]
+    pub ikm_r: Vec<u8>,
+    #[serde(rename = "skRm", with = "faster_hex::nopfx_ignorecase")]
+    pub sk_rm: Vec<u8>,
+    #[serde(rename = "pkRm", with = "faster_hex::nopfx_ignorecase")]
+    pub pk_rm: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub enc: Vec<u8>,
+    pub encryptions: Vec<HpkeEncryption>,
+    pub exports: Vec<HpkeExportVector>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct HpkeEncryption {
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub aad: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub ct: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub pt: Vec<u8>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct HpkeExportVector {
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub exporter_context: Vec<u8>,
+    #[serde(rename = "L")]
+    pub length: usize,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub exported_value: Vec<u8>,
+}
+
+impl HpkeVector {
+    fn ciphersuite(&self) -> Option<CiphersuiteId> {
+        CiphersuiteId::all_without_spec_default()
+            .into_iter()
+            .find(|cs| {
+                cs.descriptor().is_some_and(|descriptor| {
+                    u16::from(descriptor.kem) == self.kem_id
+                        && u16::from(descriptor.kdf) == self.kdf_id
+                        && u16::from(descriptor.aead) == self.aead_id
+                })
+            })
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl super::TestVector for HpkeVector {
+    const TEST_FILE: &'static str = "hpke.json";
+
+    fn is_supported(&self) -> bool {
+        self.mode == 0
+            && self
+                .ciphersuite()
+                .is_some_and(|cs| RustCryptoProvider.supports_ciphersuite(cs))
+    }
+
+    async fn execute(self) -> Result<()> {
+        let ciphersuite = self.ciphersuite().unwrap();
+        let provider = RustCryptoProvider.cipher_suite_provider(ciphersuite)?;
+
+        let keypair = provider.kem_derive(&self.ikm_r)?;
+        assert_eq_err!(keypair.sk.as_slice(), &self.sk_rm[..], "DeriveKeyPair sk");
+        assert_eq_err!(keypair.pk.as_slice(), &self.pk_rm[..], "DeriveKeyPair pk");
+        assert_eq_err!(keypair.kem_id, self.kem_id, "KEM id");
+        color_eyre::eyre::ensure!(keypair.kem_matches_ciphersuite());
+        provider.kem_public_key_validate(&keypair.pk)?;
+
+        for encryption in &self.encryptions {
+            let ciphertext = HpkeCiphertext {
+                kem_output: self.enc.clone().into(),
+                ciphertext: encryption.ct.clone().into(),
+            };
+            let plaintext = provider.hpke_open(
+                &ciphertext,
+                &keypair.sk,
+                &keypair.pk,
+                &self.info,
+                &encryption.aad,
+            )?;
+            assert_eq_err!(plaintext.as_slice(), &encryption.pt[..], "OpenBase");
+
+            let ciphertext =
+                provider.hpke_seal(&keypair.pk, &self.info, &encryption.aad, &encryption.pt)?;
+            let plaintext = provider.hpke_open(
+                &ciphertext,
+                &keypair.sk,
+                &keypair.pk,
+                &self.info,
+                &encryption.aad,
+            )?;
+            assert_eq_err!(
+                plaintext.as_slice(),
+                &encryption.pt[..],
+                "SealBase/OpenBase"
+            );
+        }
+
+        for export in &self.exports {
+            let exported = provider.hpke_export_receiver(
+                &self.enc,
+                &keypair.sk,
+                &keypair.pk,
+                &self.info,
+                &export.exporter_context,
+                export.length,
+            )?;
+            assert_eq_err!(exported.as_slice(), &export.exported_value[..], "Export");
+
+            let sender_export = provider.hpke_export_sender(
+                &keypair.pk,
+                &self.info,
+                &export.exporter_context,
+                export.length,
+            )?;
+            let receiver_export = provider.hpke_export_receiver(
+                &sender_export.kem_output,
+                &keypair.sk,
+                &keypair.pk,
+                &self.info,
+                &export.exporter_context,
+                export.length,
+            )?;
+            assert_eq_err!(
+                sender_export.export,
+                receiver_export,
+                "Sender/Receiver export"
+            );
+        }
+
+        Ok(())
+    }
+}
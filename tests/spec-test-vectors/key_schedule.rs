@@ -0,0 +1,191 @@
+use color_eyre::eyre::Result;
+use mls_spec::{
+    Serializable as _,
+    crypto::{CryptoProvider as _, rustcrypto::RustCryptoProvider},
+    defs::CiphersuiteId,
+    key_schedule::{EpochSecretExport, EpochSecrets, GroupContext},
+    test_utils::assertions::assert_eq_err,
+};
+
+#[derive(Debug, serde::Deserialize)]
+pub struct KeyScheduleVector {
+    pub cipher_suite: CiphersuiteId,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub group_id: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub initial_init_secret: Vec<u8>,
+    pub epochs: Vec<KeyScheduleEpoch>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct KeyScheduleEpoch {
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub tree_hash: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub commit_secret: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub psk_secret: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub confirmed_transcript_hash: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub group_context: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub joiner_secret: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub welcome_secret: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub init_secret: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub sender_data_secret: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub encryption_secret: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub exporter_secret: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub epoch_authenticator: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub external_secret: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub confirmation_key: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub membership_key: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub resumption_psk: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub external_pub: Vec<u8>,
+    pub exporter: ExporterVector,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct ExporterVector {
+    /// The upstream vectors use the hex string itself as the exporter label
+    pub label: String,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub context: Vec<u8>,
+    pub length: u16,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub secret: Vec<u8>,
+}
+
+#[async_trait::async_trait(?Send)]
+impl super::TestVector for KeyScheduleVector {
+    const TEST_FILE: &'static str = "key-schedule.json";
+
+    fn is_supported(&self) -> bool {
+        RustCryptoProvider.supports_ciphersuite(self.cipher_suite)
+    }
+
+    async fn execute(self) -> Result<()> {
+        let provider = RustCryptoProvider.cipher_suite_provider(self.cipher_suite)?;
+
+        let mut init_secret = self.initial_init_secret.clone();
+        for (epoch, expected) in self.epochs.iter().enumerate() {
+            let mut group_context = GroupContext::with_group_id(self.group_id.clone());
+            group_context.cipher_suite = self.cipher_suite;
+            group_context.epoch = epoch as u64;
+            group_context.tree_hash = expected.tree_hash.clone().into();
+            group_context.confirmed_transcript_hash =
+                expected.confirmed_transcript_hash.clone().into();
+            let group_context = group_context.to_tls_bytes()?;
+            assert_eq_err!(group_context, expected.group_context, "GroupContext");
+
+            let secrets = EpochSecrets::derive(
+                &provider,
+                &init_secret,
+                Some(&expected.commit_secret),
+                Some(&expected.psk_secret),
+                &group_context,
+            )?;
+
+            assert_eq_err!(
+                secrets.joiner_secret.as_slice(),
+                &expected.joiner_secret[..],
+                "joiner_secret"
+            );
+            assert_eq_err!(
+                secrets.welcome_secret.as_slice(),
+                &expected.welcome_secret[..],
+                "welcome_secret"
+            );
+            assert_eq_err!(
+                EpochSecrets::derive_welcome_secret(
+                    &provider,
+                    &expected.joiner_secret,
+                    Some(&expected.psk_secret)
+                )?
+                .as_slice(),
+                &expected.welcome_secret[..],
+                "welcome_secret (joiner)"
+            );
+
+            for (export, value) in [
+                (
+                    EpochSecretExport::SenderDataSecret,
+                    &expected.sender_data_secret,
+                ),
+                (
+                    EpochSecretExport::EncryptionSecret,
+                    &expected.encryption_secret,
+                ),
+                (EpochSecretExport::ExporterSecret, &expected.exporter_secret),
+                (EpochSecretExport::ExternalSecret, &expected.external_secret),
+                (
+                    EpochSecretExport::ConfirmationKey,
+                    &expected.confirmation_key,
+                ),
+                (EpochSecretExport::MembershipKey, &expected.membership_key),
+                (EpochSecretExport::ResumptionPsk, &expected.resumption_psk),
+                (
+                    EpochSecretExport::EpochAuthenticator,
+                    &expected.epoch_authenticator,
+                ),
+            ] {
+                assert_eq_err!(
+                    secrets.export(export).as_slice(),
+                    &value[..],
+                    format!("{export:?} at epoch {epoch}")
+                );
+            }
+
+            let joiner = EpochSecrets::from_joiner_secret(
+                &provider,
+                expected.joiner_secret.clone().into(),
+                Some(&expected.psk_secret),
+                &group_context,
+            )?;
+            assert_eq_err!(
+                joiner.epoch_secret.as_slice(),
+                secrets.epoch_secret.as_slice(),
+                "epoch_secret (joiner)"
+            );
+
+            assert_eq_err!(
+                secrets.external_key_pair(&provider)?.pk.as_slice(),
+                &expected.external_pub[..],
+                "external_pub"
+            );
+
+            assert_eq_err!(
+                secrets
+                    .mls_exporter(
+                        &provider,
+                        &expected.exporter.label,
+                        &expected.exporter.context,
+                        expected.exporter.length,
+                    )?
+                    .as_slice(),
+                &expected.exporter.secret[..],
+                "MLS-Exporter"
+            );
+
+            assert_eq_err!(
+                secrets.init_secret.as_slice(),
+                &expected.init_secret[..],
+                "init_secret"
+            );
+            init_secret = secrets.init_secret.as_slice().to_vec();
+        }
+
+        Ok(())
+    }
+}
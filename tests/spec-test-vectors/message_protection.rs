@@ -0,0 +1,191 @@
+use color_eyre::eyre::{Result, eyre};
+use mls_spec::{
+    Parsable as _, Serializable as _,
+    crypto::{CryptoProvider as _, rustcrypto::RustCryptoProvider},
+    defs::CiphersuiteId,
+    key_schedule::{GroupContext, SecretTree, SecretTreeConfig},
+    messages::{
+        ContentTypeInner, MlsMessage, MlsMessageContent, PaddingPolicy, PrivateMessage,
+        PublicMessage,
+    },
+    test_utils::assertions::assert_eq_err,
+};
+
+#[derive(Debug, serde::Deserialize)]
+pub struct MessageProtectionVector {
+    pub cipher_suite: CiphersuiteId,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub group_id: Vec<u8>,
+    pub epoch: u64,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub tree_hash: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub confirmed_transcript_hash: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub signature_pub: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub encryption_secret: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub sender_data_secret: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub membership_key: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub proposal: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub proposal_priv: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub proposal_pub: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub commit: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub commit_priv: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub commit_pub: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub application: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub application_priv: Vec<u8>,
+}
+
+impl MessageProtectionVector {
+    fn group_context(&self) -> GroupContext {
+        let mut group_context = GroupContext::with_group_id(self.group_id.clone());
+        group_context.cipher_suite = self.cipher_suite;
+        group_context.epoch = self.epoch;
+        group_context.tree_hash = self.tree_hash.clone().into();
+        group_context.confirmed_transcript_hash = self.confirmed_transcript_hash.clone().into();
+        group_context
+    }
+
+    /// The vectors are generated for a two-member group, the sender being the member at leaf 1
+    fn secret_tree(&self) -> SecretTree {
+        SecretTree::new(
+            self.encryption_secret.clone().into(),
+            2,
+            SecretTreeConfig::default(),
+        )
+    }
+}
+
+/// Serialized Proposal / Commit carried by a FramedContent, i.e. without the ContentType discriminant
+fn inner_content(content: &ContentTypeInner) -> Result<Vec<u8>> {
+    Ok(content.to_tls_bytes()?[1..].to_vec())
+}
+
+/// Serialized content carried by a FramedContent, where application data is compared raw
+fn message_content(content: &ContentTypeInner) -> Result<Vec<u8>> {
+    match content {
+        ContentTypeInner::Application { application_data } => Ok(application_data.clone()),
+        _ => inner_content(content),
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl super::TestVector for MessageProtectionVector {
+    const TEST_FILE: &'static str = "message-protection.json";
+
+    fn is_supported(&self) -> bool {
+        RustCryptoProvider.supports_ciphersuite(self.cipher_suite)
+    }
+
+    async fn execute(self) -> Result<()> {
+        let provider = RustCryptoProvider.cipher_suite_provider(self.cipher_suite)?;
+        let ctx = self.group_context();
+
+        for (name, expected, message) in [
+            ("proposal", &self.proposal, &self.proposal_pub),
+            ("commit", &self.commit, &self.commit_pub),
+        ] {
+            let MlsMessageContent::MlsPublicMessage(public_message) =
+                MlsMessage::from_tls_bytes(message)?.content
+            else {
+                return Err(eyre!("{name}_pub isn't a PublicMessage"));
+            };
+
+            let authenticated_content = public_message.clone().unprotect(
+                &provider,
+                &ctx,
+                &self.signature_pub,
+                Some(&self.membership_key),
+            )?;
+            assert_eq_err!(
+                inner_content(&authenticated_content.content.content)?,
+                *expected,
+                format!("{name}_pub content")
+            );
+
+            // Protecting the same content again gives back the same message
+            let protected = PublicMessage::protect(
+                &provider,
+                authenticated_content.clone(),
+                &ctx,
+                Some(&self.membership_key),
+            )?;
+            assert_eq_err!(protected, public_message, format!("{name}_pub protection"));
+            let roundtrip = protected.unprotect(
+                &provider,
+                &ctx,
+                &self.signature_pub,
+                Some(&self.membership_key),
+            )?;
+            assert_eq_err!(
+                roundtrip,
+                authenticated_content,
+                format!("{name} roundtrip")
+            );
+        }
+
+        // Each PrivateMessage of the vector is generated from a fresh secret tree
+        let mut sender_tree = self.secret_tree();
+        for (name, expected, message) in [
+            ("proposal", &self.proposal, &self.proposal_priv),
+            ("commit", &self.commit, &self.commit_priv),
+            ("application", &self.application, &self.application_priv),
+        ] {
+            let MlsMessageContent::MlsPrivateMessage(private_message) =
+                MlsMessage::from_tls_bytes(message)?.content
+            else {
+                return Err(eyre!("{name}_priv isn't a PrivateMessage"));
+            };
+
+            let mut receiver_tree = self.secret_tree();
+            let authenticated_content = private_message.decrypt(
+                &provider,
+                &ctx,
+                &mut receiver_tree,
+                &self.sender_data_secret,
+            )?;
+            authenticated_content.verify_signature(&provider, &self.signature_pub, &ctx)?;
+            assert_eq_err!(
+                message_content(&authenticated_content.content.content)?,
+                *expected,
+                format!("{name}_priv content")
+            );
+
+            // Encrypt the same content again and check it roundtrips
+            let private_message = PrivateMessage::encrypt(
+                &provider,
+                &authenticated_content,
+                &mut sender_tree,
+                &self.sender_data_secret,
+                &PaddingPolicy::None,
+            )?;
+            let private_message = PrivateMessage::from_tls_bytes(&private_message.to_tls_bytes()?)?;
+
+            let mut receiver_tree = self.secret_tree();
+            let roundtrip = private_message.decrypt(
+                &provider,
+                &ctx,
+                &mut receiver_tree,
+                &self.sender_data_secret,
+            )?;
+            assert_eq_err!(
+                roundtrip,
+                authenticated_content,
+                format!("{name} roundtrip")
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use color_eyre::eyre::Result;
+use mls_spec::{
+    crypto::{CryptoProvider as _, PreSharedKeyPair, rustcrypto::RustCryptoProvider},
+    defs::CiphersuiteId,
+    key_schedule::{ExternalPsk, PreSharedKeyId, PreSharedKeyIdPskType, ResumptionPskUsage},
+    test_utils::assertions::assert_eq_err,
+};
+
+#[derive(Debug, serde::Deserialize)]
+pub struct PskSecretVector {
+    pub cipher_suite: CiphersuiteId,
+    pub psks: Vec<PskVector>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub psk_secret: Vec<u8>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct PskVector {
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub psk_id: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub psk: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub psk_nonce: Vec<u8>,
+}
+
+#[async_trait::async_trait(?Send)]
+impl super::TestVector for PskSecretVector {
+    const TEST_FILE: &'static str = "psk_secret.json";
+
+    fn is_supported(&self) -> bool {
+        RustCryptoProvider.supports_ciphersuite(self.cipher_suite)
+    }
+
+    async fn execute(self) -> Result<()> {
+        let provider = RustCryptoProvider.cipher_suite_provider(self.cipher_suite)?;
+
+        let psks: Vec<PreSharedKeyPair> = self
+            .psks
+            .iter()
+            .map(|psk| PreSharedKeyPair {
+                psk_id: PreSharedKeyId {
+                    psktype: PreSharedKeyIdPskType::External(ExternalPsk {
+                        psk_id: psk.psk_id.clone(),
+                    }),
+                    psk_nonce: psk.psk_nonce.clone().into(),
+                },
+                psk_secret: psk.psk.clone().into(),
+            })
+            .collect();
+
+        let psk_secret =
+            PreSharedKeyPair::psk_secret(&provider, &psks, ResumptionPskUsage::Application)?;
+        assert_eq_err!(psk_secret.as_slice(), &self.psk_secret[..], "psk_secret");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use color_eyre::eyre::{Result, ensure};
+use mls_spec::{
+    MlsSpecError,
+    crypto::{CryptoProvider as _, rustcrypto::RustCryptoProvider},
+    defs::CiphersuiteId,
+    key_schedule::{RatchetKind, SecretTree, SecretTreeConfig},
+    messages::SenderData,
+    test_utils::assertions::assert_eq_err,
+};
+
+#[derive(Debug, serde::Deserialize)]
+pub struct SecretTreeVector {
+    pub cipher_suite: CiphersuiteId,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub encryption_secret: Vec<u8>,
+    pub sender_data: SecretTreeSenderData,
+    pub leaves: Vec<Vec<SecretTreeLeafGeneration>>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct SecretTreeSenderData {
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub sender_data_secret: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub ciphertext: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub key: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub nonce: Vec<u8>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct SecretTreeLeafGeneration {
+    pub generation: u32,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub handshake_key: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub handshake_nonce: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub application_key: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub application_nonce: Vec<u8>,
+}
+
+#[async_trait::async_trait(?Send)]
+impl super::TestVector for SecretTreeVector {
+    const TEST_FILE: &'static str = "secret-tree.json";
+
+    fn is_supported(&self) -> bool {
+        RustCryptoProvider.supports_ciphersuite(self.cipher_suite)
+    }
+
+    async fn execute(self) -> Result<()> {
+        let provider = RustCryptoProvider.cipher_suite_provider(self.cipher_suite)?;
+        let leaf_count = self.leaves.len() as u32;
+
+        let (sender_data_key, sender_data_nonce) = SenderData::key_nonce(
+            &provider,
+            &self.sender_data.sender_data_secret,
+            &self.sender_data.ciphertext,
+        )?;
+        assert_eq_err!(
+            sender_data_key.as_slice(),
+            &self.sender_data.key[..],
+            "sender_data_key"
+        );
+        assert_eq_err!(
+            sender_data_nonce.as_slice(),
+            &self.sender_data.nonce[..],
+            "sender_data_nonce"
+        );
+
+        let mut tree = SecretTree::new(
+            self.encryption_secret.clone().into(),
+            leaf_count,
+            SecretTreeConfig::default(),
+        );
+        for (leaf, generations) in self.leaves.iter().enumerate() {
+            let leaf = leaf as u32;
+            for expected in generations {
+                for (kind, key, nonce) in [
+                    (
+                        RatchetKind::Handshake,
+                        &expected.handshake_key,
+                        &expected.handshake_nonce,
+                    ),
+                    (
+                        RatchetKind::Application,
+                        &expected.application_key,
+                        &expected.application_nonce,
+                    ),
+                ] {
+                    let key_nonce = tree.key_nonce(&provider, leaf, kind, expected.generation)?;
+                    assert_eq_err!(key_nonce.generation, expected.generation);
+                    assert_eq_err!(
+                        key_nonce.key.as_slice(),
+                        &key[..],
+                        format!("{kind:?} key of leaf {leaf}")
+                    );
+                    assert_eq_err!(
+                        key_nonce.nonce.as_slice(),
+                        &nonce[..],
+                        format!("{kind:?} nonce of leaf {leaf}")
+                    );
+
+                    // Keys can only be obtained once
+                    ensure!(matches!(
+                        tree.key_nonce(&provider, leaf, kind, expected.generation),
+                        Err(MlsSpecError::SecretTreeGenerationConsumed(_))
+                    ));
+                }
+            }
+        }
+
+        // Out-of-order delivery on a fresh tree: the sender ratchets forward while the receiver
+        // only retains the last `out_of_order_tolerance` skipped keys
+        let config = SecretTreeConfig {
+            out_of_order_tolerance: 2,
+            maximum_forward_distance: 10,
+        };
+        let mut sender = SecretTree::new(self.encryption_secret.clone().into(), leaf_count, config);
+        let mut receiver =
+            SecretTree::new(self.encryption_secret.clone().into(), leaf_count, config);
+        let sent = (0..4)
+            .map(|_| sender.next_key_nonce(&provider, 0, RatchetKind::Application))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq_err!(
+            receiver.key_nonce(&provider, 0, RatchetKind::Application, 3)?,
+            sent[3]
+        );
+        assert_eq_err!(
+            receiver.key_nonce(&provider, 0, RatchetKind::Application, 1)?,
+            sent[1]
+        );
+        assert_eq_err!(
+            receiver.key_nonce(&provider, 0, RatchetKind::Application, 2)?,
+            sent[2]
+        );
+        ensure!(matches!(
+            receiver.key_nonce(&provider, 0, RatchetKind::Application, 0),
+            Err(MlsSpecError::SecretTreeGenerationConsumed(0))
+        ));
+        ensure!(matches!(
+            receiver.key_nonce(&provider, 0, RatchetKind::Application, 15),
+            Err(MlsSpecError::SecretTreeGenerationTooFarAhead { .. })
+        ));
+        ensure!(matches!(
+            receiver.key_nonce(
+                &provider,
+                leaf_count.next_power_of_two(),
+                RatchetKind::Handshake,
+                0
+            ),
+            Err(MlsSpecError::SecretTreeLeafOutOfBounds(_))
+        ));
+
+        Ok(())
+    }
+}
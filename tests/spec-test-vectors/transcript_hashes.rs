@@ -0,0 +1,73 @@
+use color_eyre::eyre::{Result, eyre};
+use mls_spec::{
+    Parsable as _,
+    crypto::{CipherSuiteProvider as _, CryptoProvider as _, rustcrypto::RustCryptoProvider},
+    defs::CiphersuiteId,
+    key_schedule::GroupContext,
+    messages::AuthenticatedContent,
+    test_utils::assertions::assert_eq_err,
+};
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TranscriptHashesVector {
+    pub cipher_suite: CiphersuiteId,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub confirmation_key: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub authenticated_content: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub interim_transcript_hash_before: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub confirmed_transcript_hash_after: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub interim_transcript_hash_after: Vec<u8>,
+}
+
+#[async_trait::async_trait(?Send)]
+impl super::TestVector for TranscriptHashesVector {
+    const TEST_FILE: &'static str = "transcript-hashes.json";
+
+    fn is_supported(&self) -> bool {
+        RustCryptoProvider.supports_ciphersuite(self.cipher_suite)
+    }
+
+    async fn execute(self) -> Result<()> {
+        let provider = RustCryptoProvider.cipher_suite_provider(self.cipher_suite)?;
+        let authenticated_content =
+            AuthenticatedContent::from_tls_bytes(&self.authenticated_content)?;
+
+        let confirmation_tag = authenticated_content
+            .auth
+            .confirmation_tag
+            .as_ref()
+            .ok_or_else(|| eyre!("authenticated_content has no confirmation tag"))?;
+        let expected_confirmation_tag = provider.mac(
+            &self.confirmation_key,
+            &self.confirmed_transcript_hash_after,
+        )?;
+        assert_eq_err!(
+            confirmation_tag,
+            &expected_confirmation_tag,
+            "confirmation_tag"
+        );
+
+        let mut group_context = GroupContext::default();
+        let interim_transcript_hash = group_context.update_transcript_hashes(
+            &provider,
+            &authenticated_content,
+            &self.interim_transcript_hash_before,
+        )?;
+        assert_eq_err!(
+            group_context.confirmed_transcript_hash.as_slice(),
+            &self.confirmed_transcript_hash_after[..],
+            "confirmed_transcript_hash"
+        );
+        assert_eq_err!(
+            interim_transcript_hash.as_slice(),
+            &self.interim_transcript_hash_after[..],
+            "interim_transcript_hash"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use color_eyre::eyre::Result;
+use mls_spec::{
+    defs::{LeafIndex, NodeIndex},
+    test_utils::assertions::assert_eq_err,
+    tree::math,
+};
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TreeMathVector {
+    pub n_leaves: LeafIndex,
+    pub n_nodes: u32,
+    pub root: NodeIndex,
+    pub left: Vec<Option<NodeIndex>>,
+    pub right: Vec<Option<NodeIndex>>,
+    pub parent: Vec<Option<NodeIndex>>,
+    pub sibling: Vec<Option<NodeIndex>>,
+}
+
+#[async_trait::async_trait(?Send)]
+impl super::TestVector for TreeMathVector {
+    const TEST_FILE: &'static str = "tree-math.json";
+
+    async fn execute(self) -> Result<()> {
+        let n = self.n_leaves;
+        assert_eq_err!(math::node_width(n), self.n_nodes, "n_nodes");
+        assert_eq_err!(math::root(n), self.root, "root");
+
+        for x in 0..self.n_nodes {
+            let i = x as usize;
+            assert_eq_err!(math::left(x), self.left[i], format!("left({x})"));
+            assert_eq_err!(math::right(x), self.right[i], format!("right({x})"));
+            assert_eq_err!(math::parent(x, n), self.parent[i], format!("parent({x})"));
+            assert_eq_err!(
+                math::sibling(x, n),
+                self.sibling[i],
+                format!("sibling({x})")
+            );
+
+            let direct_path = math::direct_path(x, n);
+            let copath = math::copath(x, n);
+            assert_eq_err!(direct_path.len(), copath.len(), format!("copath({x})"));
+            assert_eq_err!(
+                direct_path.last().copied().unwrap_or(x),
+                self.root,
+                format!("direct_path({x})")
+            );
+            for (node, (ancestor, sibling)) in std::iter::once(x)
+                .chain(direct_path.iter().copied())
+                .zip(direct_path.iter().zip(&copath))
+            {
+                assert_eq_err!(self.parent[node as usize], Some(*ancestor));
+                assert_eq_err!(self.sibling[node as usize], Some(*sibling));
+                assert_eq_err!(math::common_ancestor(node, *sibling), *ancestor);
+            }
+
+            if let Some(leaf) = math::node_to_leaf(x) {
+                assert_eq_err!(math::leaf_to_node(leaf), x);
+                assert_eq_err!(math::level(x), 0);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use color_eyre::eyre::{Result, eyre};
+use mls_spec::{
+    Parsable as _, Serializable as _, defs::LeafIndex, group::proposals::Proposal,
+    test_utils::assertions::assert_eq_err, tree::RatchetTree,
+};
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TreeOperationsVector {
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub tree_before: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub proposal: Vec<u8>,
+    pub proposal_sender: LeafIndex,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub tree_after: Vec<u8>,
+}
+
+#[async_trait::async_trait(?Send)]
+impl super::TestVector for TreeOperationsVector {
+    const TEST_FILE: &'static str = "tree-operations.json";
+
+    async fn execute(self) -> Result<()> {
+        let mut tree = RatchetTree::from_tls_bytes(&self.tree_before)?;
+        let leaf_count = tree.leaf_count();
+
+        match Proposal::from_tls_bytes(&self.proposal)? {
+            Proposal::Add(add) => {
+                let leaf = tree.add_leaf(add.key_package.leaf_node.clone());
+                if leaf == leaf_count {
+                    assert_eq_err!(tree.leaf_count(), leaf_count * 2, "extended leaf count");
+                }
+            }
+            Proposal::Update(update) => {
+                tree.update_leaf(self.proposal_sender, update.leaf_node)?;
+            }
+            Proposal::Remove(remove) => {
+                tree.remove_leaf(remove.removed)?;
+                assert_eq_err!(
+                    tree.leaf_node(remove.removed).is_none(),
+                    true,
+                    "removed leaf"
+                );
+            }
+            proposal => return Err(eyre!("Unexpected proposal {proposal:?}")),
+        }
+
+        assert_eq_err!(tree.to_tls_bytes()?, self.tree_after, "tree_after");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use color_eyre::eyre::Result;
+use mls_spec::{
+    Parsable as _,
+    crypto::{CryptoProvider as _, rustcrypto::RustCryptoProvider},
+    defs::{CiphersuiteId, NodeIndex},
+    key_schedule::GroupContext,
+    test_utils::assertions::assert_eq_err,
+    tree::{RatchetTree, math},
+};
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TreeValidationVector {
+    pub cipher_suite: CiphersuiteId,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub tree: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub group_id: Vec<u8>,
+    pub resolutions: Vec<Vec<NodeIndex>>,
+    pub tree_hashes: Vec<TreeHashHex>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(transparent)]
+pub struct TreeHashHex(#[serde(with = "faster_hex::nopfx_ignorecase")] Vec<u8>);
+
+#[async_trait::async_trait(?Send)]
+impl super::TestVector for TreeValidationVector {
+    const TEST_FILE: &'static str = "tree-validation.json";
+
+    fn is_supported(&self) -> bool {
+        RustCryptoProvider.supports_ciphersuite(self.cipher_suite)
+    }
+
+    async fn execute(self) -> Result<()> {
+        let provider = RustCryptoProvider.cipher_suite_provider(self.cipher_suite)?;
+        let tree = RatchetTree::from_tls_bytes(&self.tree)?;
+
+        for (x, expected) in self.resolutions.iter().enumerate() {
+            assert_eq_err!(
+                &tree.resolution(x as NodeIndex),
+                expected,
+                format!("resolution of node {x}")
+            );
+        }
+
+        let leaf_count = tree.leaf_count();
+        for leaf in 0..leaf_count {
+            let x = math::leaf_to_node(leaf);
+            let expected = math::direct_path(x, leaf_count)
+                .into_iter()
+                .zip(math::copath(x, leaf_count))
+                .filter(|(_, copath_child)| !self.resolutions[*copath_child as usize].is_empty())
+                .map(|(parent, _)| parent)
+                .collect::<Vec<_>>();
+            assert_eq_err!(
+                tree.filtered_direct_path(leaf),
+                expected,
+                format!("filtered direct path of leaf {leaf}")
+            );
+        }
+
+        for (x, expected) in self.tree_hashes.iter().enumerate() {
+            let tree_hash = tree.tree_hash(&provider, x as NodeIndex)?;
+            assert_eq_err!(
+                tree_hash.as_slice(),
+                &expected.0[..],
+                format!("tree hash of node {x}")
+            );
+        }
+
+        tree.verify_parent_hashes(&provider)?;
+
+        let mut group_context = GroupContext::with_group_id(self.group_id.clone());
+        group_context.cipher_suite = self.cipher_suite;
+        group_context.tree_hash = tree.root_tree_hash(&provider)?;
+        tree.verify_tree_hash(&provider, &group_context)?;
+        tree.validate(&provider, &group_context)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+use color_eyre::eyre::{Result, eyre};
+use mls_spec::{
+    Parsable as _,
+    crypto::{CryptoProvider as _, rustcrypto::RustCryptoProvider},
+    defs::{CiphersuiteId, LeafIndex, NodeIndex},
+    key_schedule::GroupContext,
+    test_utils::assertions::assert_eq_err,
+    tree::{RatchetTree, TreeKemPrivate, UpdatePath, math},
+};
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TreeKemVector {
+    pub cipher_suite: CiphersuiteId,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub group_id: Vec<u8>,
+    pub epoch: u64,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub confirmed_transcript_hash: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub ratchet_tree: Vec<u8>,
+    pub leaves_private: Vec<TreeKemLeafPrivate>,
+    pub update_paths: Vec<TreeKemUpdatePath>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TreeKemLeafPrivate {
+    pub index: LeafIndex,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub encryption_priv: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub signature_priv: Vec<u8>,
+    pub path_secrets: Vec<TreeKemPathSecret>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TreeKemPathSecret {
+    pub node: NodeIndex,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub path_secret: Vec<u8>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TreeKemUpdatePath {
+    pub sender: LeafIndex,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub update_path: Vec<u8>,
+    /// Path secret obtained by each leaf, indexed by leaf index
+    pub path_secrets: Vec<Option<PathSecretHex>>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub commit_secret: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub tree_hash_after: Vec<u8>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(transparent)]
+pub struct PathSecretHex(#[serde(with = "faster_hex::nopfx_ignorecase")] Vec<u8>);
+
+impl TreeKemVector {
+    /// Provisional GroupContext of the epoch the UpdatePaths are sent in, the tree hash being set when they're applied
+    fn group_context(&self) -> GroupContext {
+        let mut group_context = GroupContext::with_group_id(self.group_id.clone());
+        group_context.cipher_suite = self.cipher_suite;
+        group_context.epoch = self.epoch;
+        group_context.confirmed_transcript_hash = self.confirmed_transcript_hash.clone().into();
+        group_context
+    }
+
+    fn private(&self, leaf: &TreeKemLeafPrivate) -> TreeKemPrivate {
+        let mut private = TreeKemPrivate::new(leaf.index, leaf.encryption_priv.clone().into());
+        private.path_secrets = leaf
+            .path_secrets
+            .iter()
+            .map(|path_secret| (path_secret.node, path_secret.path_secret.clone().into()))
+            .collect();
+        private
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl super::TestVector for TreeKemVector {
+    const TEST_FILE: &'static str = "treekem.json";
+
+    fn is_supported(&self) -> bool {
+        RustCryptoProvider.supports_ciphersuite(self.cipher_suite)
+    }
+
+    async fn execute(self) -> Result<()> {
+        let provider = RustCryptoProvider.cipher_suite_provider(self.cipher_suite)?;
+        let tree = RatchetTree::from_tls_bytes(&self.ratchet_tree)?;
+        tree.verify_parent_hashes(&provider)?;
+
+        // The path secrets held by each member match the public keys of the tree
+        for leaf in &self.leaves_private {
+            for path_secret in &leaf.path_secrets {
+                let key_pair = TreeKemPrivate::node_key_pair(&provider, &path_secret.path_secret)?;
+                let parent_node = tree
+                    .parent_node(path_secret.node)
+                    .ok_or_else(|| eyre!("node {} is blank", path_secret.node))?;
+                assert_eq_err!(
+                    &key_pair.pk,
+                    &parent_node.encryption_key,
+                    format!("public key of node {}", path_secret.node)
+                );
+            }
+        }
+
+        for (i, update_path) in self.update_paths.iter().enumerate() {
+            let sender = update_path.sender;
+            let path = UpdatePath::from_tls_bytes(&update_path.update_path)?;
+
+            for leaf in &self.leaves_private {
+                if leaf.index == sender {
+                    continue;
+                }
+
+                let mut tree = tree.clone();
+                let mut group_context = self.group_context();
+                let mut private = self.private(leaf);
+                let commit_secret = tree.apply_update_path(
+                    &provider,
+                    sender,
+                    &path,
+                    &mut group_context,
+                    &[],
+                    &mut private,
+                )?;
+                assert_eq_err!(
+                    commit_secret.as_slice(),
+                    &update_path.commit_secret[..],
+                    format!("commit_secret of leaf {} from {sender}", leaf.index)
+                );
+                assert_eq_err!(
+                    group_context.tree_hash.as_slice(),
+                    &update_path.tree_hash_after[..],
+                    "tree_hash_after"
+                );
+                tree.verify_parent_hashes(&provider)?;
+
+                let ancestor = math::common_ancestor(
+                    math::leaf_to_node(leaf.index),
+                    math::leaf_to_node(sender),
+                );
+                let expected_path_secret = update_path
+                    .path_secrets
+                    .get(leaf.index as usize)
+                    .and_then(Option::as_ref)
+                    .ok_or_else(|| eyre!("missing path secret for leaf {}", leaf.index))?;
+                assert_eq_err!(
+                    private.path_secrets.get(&ancestor).map(|s| s.as_slice()),
+                    Some(&expected_path_secret.0[..]),
+                    format!("path secret of leaf {} at node {ancestor}", leaf.index)
+                );
+            }
+
+            // Generate a fresh UpdatePath from the first sender and check every member can process it
+            let Some(sender_private) = self
+                .leaves_private
+                .iter()
+                .find(|l| i == 0 && l.index == sender)
+            else {
+                continue;
+            };
+            let mut sender_tree = tree.clone();
+            let mut sender_group_context = self.group_context();
+            let leaf_node = sender_tree
+                .leaf_node(sender)
+                .ok_or_else(|| eyre!("sender leaf {sender} is blank"))?
+                .clone();
+            let generated = sender_tree.generate_update_path(
+                &provider,
+                sender,
+                leaf_node,
+                &sender_private.signature_priv,
+                &mut sender_group_context,
+                &[],
+            )?;
+            sender_tree.verify_parent_hashes(&provider)?;
+
+            for leaf in self.leaves_private.iter().filter(|l| l.index != sender) {
+                let mut tree = tree.clone();
+                let mut group_context = self.group_context();
+                let mut private = self.private(leaf);
+                let commit_secret = tree.apply_update_path(
+                    &provider,
+                    sender,
+                    &generated.update_path,
+                    &mut group_context,
+                    &[],
+                    &mut private,
+                )?;
+                assert_eq_err!(
+                    &commit_secret,
+                    &generated.commit_secret,
+                    format!("generated commit_secret of leaf {}", leaf.index)
+                );
+                assert_eq_err!(
+                    &group_context.tree_hash,
+                    &sender_group_context.tree_hash,
+                    "generated tree hash"
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use color_eyre::eyre::{Result, eyre};
+use mls_spec::{
+    Parsable as _,
+    crypto::{CryptoProvider as _, LabeledCrypto as _, rustcrypto::RustCryptoProvider},
+    defs::{CiphersuiteId, labels::PublicKeyEncryptionLabel},
+    group::welcome::GroupSecrets,
+    key_schedule::GroupContext,
+    messages::{MlsMessage, MlsMessageContent},
+    test_utils::assertions::assert_eq_err,
+};
+
+#[derive(Debug, serde::Deserialize)]
+pub struct WelcomeVector {
+    pub cipher_suite: CiphersuiteId,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub init_priv: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub key_package: Vec<u8>,
+    #[serde(with = "faster_hex::nopfx_ignorecase")]
+    pub welcome: Vec<u8>,
+}
+
+#[async_trait::async_trait(?Send)]
+impl super::TestVector for WelcomeVector {
+    const TEST_FILE: &'static str = "welcome.json";
+
+    fn is_supported(&self) -> bool {
+        RustCryptoProvider.supports_ciphersuite(self.cipher_suite)
+    }
+
+    async fn execute(self) -> Result<()> {
+        let provider = RustCryptoProvider.cipher_suite_provider(self.cipher_suite)?;
+
+        let MlsMessageContent::KeyPackage(key_package) =
+            MlsMessage::from_tls_bytes(&self.key_package)?.content
+        else {
+            return Err(eyre!("key_package isn't a KeyPackage"));
+        };
+        assert_eq_err!(key_package.cipher_suite, self.cipher_suite);
+        key_package.verify_signature(&provider)?;
+        key_package.leaf_node.verify_signature(&provider, None)?;
+
+        let mut group_context = GroupContext::with_group_id(b"group".to_vec());
+        group_context.cipher_suite = self.cipher_suite;
+        key_package.validate(&provider, &group_context)?;
+
+        let MlsMessageContent::Welcome(welcome) =
+            MlsMessage::from_tls_bytes(&self.welcome)?.content
+        else {
+            return Err(eyre!("welcome isn't a Welcome"));
+        };
+        assert_eq_err!(welcome.cipher_suite, self.cipher_suite);
+
+        let key_package_ref = key_package.key_package_ref(&provider)?;
+        let encrypted_group_secrets = welcome
+            .secrets_for(&key_package_ref)
+            .ok_or_else(|| eyre!("No GroupSecrets for our KeyPackageRef"))?;
+
+        let group_secrets = provider.decrypt_with_label(
+            &self.init_priv.clone().into(),
+            &key_package.init_key,
+            &PublicKeyEncryptionLabel::Welcome,
+            &welcome.encrypted_group_info,
+            &encrypted_group_secrets.encrypted_group_secrets,
+        )?;
+        GroupSecrets::from_tls_bytes(&group_secrets)?;
+
+        Ok(())
+    }
+}